@@ -82,16 +82,47 @@ macro_rules! unsafe_tsk_ragged_char_column_access {
             if start == stop {
                 Ok(None)
             } else {
-                let mut buffer = String::new();
-                for i in start..stop {
-                    buffer.push(unsafe { *$array.offset(i as isize) as u8 as char });
-                }
-                Ok(Some(buffer))
+                let bytes = unsafe {
+                    std::slice::from_raw_parts(
+                        $array.offset(start as isize) as *const u8,
+                        (stop - start) as usize,
+                    )
+                };
+                Ok(Some(String::from_utf8_lossy(bytes).into_owned()))
             }
         }
     }};
 }
 
+macro_rules! metadata_schema_to_string {
+    ($table: expr) => {{
+        if $table.metadata_schema_length == 0 {
+            None
+        } else {
+            let bytes = unsafe {
+                std::slice::from_raw_parts(
+                    $table.metadata_schema as *const u8,
+                    $table.metadata_schema_length as usize,
+                )
+            };
+            Some(String::from_utf8_lossy(bytes).into_owned())
+        }
+    }};
+}
+
+macro_rules! set_table_metadata_schema {
+    ($self: ident, $table: ident, $function: ident, $schema: expr) => {{
+        let rv = unsafe {
+            ll_bindings::$function(
+                &mut (*$self.as_mut_ptr()).$table,
+                $schema.as_ptr() as *const libc::c_char,
+                $schema.len() as tsk_size_t,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }};
+}
+
 macro_rules! drop_for_tskit_type {
     ($name: ident, $drop: ident) => {
         impl Drop for $name {
@@ -200,6 +231,36 @@ macro_rules! table_row_access {
     };
 }
 
+// Implement Iterator, DoubleEndedIterator, and ExactSizeIterator
+// for a TableIterator type alias, given the row type and the
+// function that makes a row from a table and a row id.
+macro_rules! table_iterator_impls {
+    ($iterator: ident, $row: ty, $make_row: ident) => {
+        impl<'a> Iterator for $iterator<'a> {
+            type Item = $row;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let row = self.next_row()?;
+                $make_row(&self.table, row)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let n = self.remaining();
+                (n, Some(n))
+            }
+        }
+
+        impl<'a> DoubleEndedIterator for $iterator<'a> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                let row = self.next_row_back()?;
+                $make_row(&self.table, row)
+            }
+        }
+
+        impl<'a> ExactSizeIterator for $iterator<'a> {}
+    };
+}
+
 macro_rules! iterator_for_nodeiterator {
     ($ty: ty) => {
         impl Iterator for $ty {
@@ -0,0 +1,138 @@
+use crate::bindings as ll_bindings;
+use crate::tsk_id_t;
+
+/// The edges removed and inserted when moving
+/// from one tree to the next.
+///
+/// These are not created directly.
+/// Instead, use [`crate::TreeSequence::edge_differences_iter`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct EdgeDifferences {
+    /// The genomic interval `[left, right)` of the tree
+    /// resulting from applying the differences.
+    pub interval: (f64, f64),
+    /// Ids of the edges removed, in removal order.
+    pub removed: Vec<tsk_id_t>,
+    /// Ids of the edges inserted, in insertion order.
+    pub inserted: Vec<tsk_id_t>,
+}
+
+/// An [`Iterator`] over [`EdgeDifferences`].
+///
+/// See [`crate::TreeSequence::edge_differences_iter`].
+pub struct EdgeDiffsIterator<'a> {
+    edge_left: &'a [f64],
+    edge_right: &'a [f64],
+    insertion_order: &'a [tsk_id_t],
+    removal_order: &'a [tsk_id_t],
+    sequence_length: f64,
+    insertion_index: usize,
+    removal_index: usize,
+    left: f64,
+}
+
+impl<'a> EdgeDiffsIterator<'a> {
+    pub(crate) fn new(tables: &'a ll_bindings::tsk_table_collection_t) -> Self {
+        let num_edges = tables.edges.num_rows as usize;
+        let (edge_left, edge_right, insertion_order, removal_order) = unsafe {
+            (
+                std::slice::from_raw_parts(tables.edges.left, num_edges),
+                std::slice::from_raw_parts(tables.edges.right, num_edges),
+                std::slice::from_raw_parts(tables.indexes.edge_insertion_order, num_edges),
+                std::slice::from_raw_parts(tables.indexes.edge_removal_order, num_edges),
+            )
+        };
+        Self {
+            edge_left,
+            edge_right,
+            insertion_order,
+            removal_order,
+            sequence_length: tables.sequence_length,
+            insertion_index: 0,
+            removal_index: 0,
+            left: 0.,
+        }
+    }
+}
+
+impl<'a> Iterator for EdgeDiffsIterator<'a> {
+    type Item = EdgeDifferences;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let num_edges = self.insertion_order.len();
+        if self.insertion_index >= num_edges && self.left >= self.sequence_length {
+            return None;
+        }
+        let mut removed = vec![];
+        while self.removal_index < num_edges
+            && self.edge_right[self.removal_order[self.removal_index] as usize] == self.left
+        {
+            removed.push(self.removal_order[self.removal_index]);
+            self.removal_index += 1;
+        }
+        let mut inserted = vec![];
+        while self.insertion_index < num_edges
+            && self.edge_left[self.insertion_order[self.insertion_index] as usize] == self.left
+        {
+            inserted.push(self.insertion_order[self.insertion_index]);
+            self.insertion_index += 1;
+        }
+        let mut right = self.sequence_length;
+        if self.insertion_index < num_edges {
+            right = f64::min(
+                right,
+                self.edge_left[self.insertion_order[self.insertion_index] as usize],
+            );
+        }
+        if self.removal_index < num_edges {
+            right = f64::min(
+                right,
+                self.edge_right[self.removal_order[self.removal_index] as usize],
+            );
+        }
+        let interval = (self.left, right);
+        self.left = right;
+        Some(EdgeDifferences {
+            interval,
+            removed,
+            inserted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_fixtures::treeseq_from_small_table_collection_two_trees;
+    use crate::TableAccess;
+
+    #[test]
+    fn test_edge_differences() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let edges = treeseq.edges();
+        let diffs = treeseq.edge_differences_iter().collect::<Vec<_>>();
+        assert_eq!(diffs.len(), treeseq.num_trees() as usize);
+
+        let mut current = vec![];
+        let mut all_inserted = vec![];
+        for d in diffs.iter() {
+            for e in d.removed.iter() {
+                let i = current.iter().position(|x| x == e).unwrap();
+                current.remove(i);
+            }
+            current.extend_from_slice(&d.inserted);
+            all_inserted.extend_from_slice(&d.inserted);
+            for &e in current.iter() {
+                assert!(edges.left(e).unwrap() <= d.interval.0);
+                assert!(edges.right(e).unwrap() >= d.interval.1);
+            }
+        }
+        assert_eq!(diffs[0].interval, (0., 500.));
+        assert_eq!(diffs[1].interval, (500., 1000.));
+        assert!(diffs[0].removed.is_empty());
+        all_inserted.sort_unstable();
+        assert_eq!(
+            all_inserted,
+            (0..edges.num_rows() as crate::tsk_id_t).collect::<Vec<_>>()
+        );
+    }
+}
@@ -42,26 +42,19 @@ fn make_edge_table_row(table: &EdgeTable, pos: tsk_id_t) -> Option<EdgeTableRow>
 pub type EdgeTableRefIterator<'a> = crate::table_iterator::TableIterator<&'a EdgeTable<'a>>;
 pub type EdgeTableIterator<'a> = crate::table_iterator::TableIterator<EdgeTable<'a>>;
 
-impl<'a> Iterator for EdgeTableRefIterator<'a> {
-    type Item = EdgeTableRow;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_edge_table_row(self.table, self.pos);
-        self.pos += 1;
-        rv
-    }
-}
+table_iterator_impls!(EdgeTableRefIterator, EdgeTableRow, make_edge_table_row);
 
-impl<'a> Iterator for EdgeTableIterator<'a> {
+impl<'a> IntoIterator for &'a EdgeTable<'a> {
     type Item = EdgeTableRow;
+    type IntoIter = EdgeTableRefIterator<'a>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_edge_table_row(&self.table, self.pos);
-        self.pos += 1;
-        rv
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
+table_iterator_impls!(EdgeTableIterator, EdgeTableRow, make_edge_table_row);
+
 /// An immutable view of an edge table.
 ///
 /// These are not created directly.
@@ -81,6 +74,13 @@ impl<'a> EdgeTable<'a> {
         self.table_.num_rows
     }
 
+    /// Return the metadata schema of the table.
+    ///
+    /// Returns `None` if no schema has been set.
+    pub fn metadata_schema(&self) -> Option<String> {
+        metadata_schema_to_string!(self.table_)
+    }
+
     /// Return the ``parent`` value from row ``row`` of the table.
     ///
     /// # Errors
@@ -133,7 +133,7 @@ impl<'a> EdgeTable<'a> {
     /// The value of the iterator is [`EdgeTableRow`].
     ///
     pub fn iter(&self) -> EdgeTableRefIterator {
-        crate::table_iterator::make_table_iterator::<&EdgeTable<'a>>(&self)
+        crate::table_iterator::make_table_iterator::<&EdgeTable<'a>>(&self, self.num_rows())
     }
 
     /// Return row `r` of the table.
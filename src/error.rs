@@ -21,6 +21,13 @@ pub enum TskitError {
     /// Wrapper around tskit C API error codes.
     #[error("{}", get_tskit_error_message(*code))]
     ErrorCode { code: i32 },
+    /// A redirection of [``std::io::Error``]
+    #[error("{value}")]
+    IOError {
+        /// The redirected error
+        #[from]
+        value: std::io::Error,
+    },
     /// A redirection of [``crate::metadata::MetadataError``]
     #[error("{value:?}")]
     MetadataError {
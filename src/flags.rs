@@ -67,6 +67,27 @@ bitflags! {
     }
 }
 
+impl SimplificationOptions {
+    /// Remove sites, populations, and individuals that are not
+    /// referenced after simplification.
+    ///
+    /// This matches the default behavior of the Python API.
+    /// In contrast, [`SimplificationOptions::default`] leaves
+    /// these tables unfiltered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::SimplificationOptions as SO;
+    /// let flags = SO::filter_all();
+    /// assert_eq!(flags, SO::FILTER_SITES | SO::FILTER_POPULATIONS | SO::FILTER_INDIVIDUALS);
+    /// assert!(SO::default().is_empty());
+    /// ```
+    pub fn filter_all() -> Self {
+        Self::FILTER_SITES | Self::FILTER_POPULATIONS | Self::FILTER_INDIVIDUALS
+    }
+}
+
 bitflags! {
     /// Modify behavior of [`crate::TableCollection::clear`].
     #[derive(Default)]
@@ -81,13 +102,29 @@ bitflags! {
 
 bitflags! {
     /// Modify behavior of [`crate::TableCollection::equals`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableEqualityOptions as TEO;
+    /// let flags = TEO::IGNORE_METADATA | TEO::IGNORE_PROVENANCE;
+    /// assert!(flags.contains(TEO::IGNORE_METADATA));
+    /// assert!(!flags.contains(TEO::IGNORE_TIMESTAMPS));
+    /// ```
     #[derive(Default)]
     pub struct TableEqualityOptions : tsk_flags_t {
         /// Default behavior.
         const NONE = 0;
+        /// Do not compare the metadata of any table,
+        /// nor the metadata schemas.
+        /// Implies [`TableEqualityOptions::IGNORE_TS_METADATA`].
         const IGNORE_METADATA = ll_bindings::TSK_CMP_IGNORE_METADATA;
+        /// Do not compare the top-level metadata
+        /// and metadata schema of the table collection.
         const IGNORE_TS_METADATA = ll_bindings::TSK_CMP_IGNORE_TS_METADATA;
+        /// Do not compare the provenance tables.
         const IGNORE_PROVENANCE = ll_bindings::TSK_CMP_IGNORE_PROVENANCE;
+        /// Do not compare the timestamps of provenance records.
         const IGNORE_TIMESTAMPS = ll_bindings::TSK_CMP_IGNORE_TIMESTAMPS;
     }
 }
@@ -103,9 +140,86 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Modify behavior of [`crate::TableCollection::check_integrity`].
+    ///
+    /// The default (`NONE`) checks the integrity of memory and
+    /// of references between tables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableIntegrityCheckOptions as TICO;
+    /// let flags = TICO::CHECK_EDGE_ORDERING | TICO::CHECK_SITE_ORDERING;
+    /// assert!(flags.contains(TICO::CHECK_EDGE_ORDERING));
+    /// assert!(!flags.contains(TICO::CHECK_TREES));
+    /// ```
+    #[derive(Default)]
+    pub struct TableIntegrityCheckOptions : tsk_flags_t {
+        /// Default behavior.
+        const NONE = 0;
+        /// Check edge ordering constraints for a tree sequence.
+        const CHECK_EDGE_ORDERING = ll_bindings::TSK_CHECK_EDGE_ORDERING;
+        /// Check that sites are in nondecreasing position order.
+        const CHECK_SITE_ORDERING = ll_bindings::TSK_CHECK_SITE_ORDERING;
+        /// Check for any duplicate site positions.
+        const CHECK_SITE_DUPLICATES = ll_bindings::TSK_CHECK_SITE_DUPLICATES;
+        /// Check constraints on the ordering of mutations.
+        /// Any non-null mutation parents and known times
+        /// are checked for ordering constraints.
+        const CHECK_MUTATION_ORDERING = ll_bindings::TSK_CHECK_MUTATION_ORDERING;
+        /// Check that individual parents are before children, where specified.
+        const CHECK_INDIVIDUAL_ORDERING = ll_bindings::TSK_CHECK_INDIVIDUAL_ORDERING;
+        /// Check that the table indexes exist and contain valid edge references.
+        const CHECK_INDEXES = ll_bindings::TSK_CHECK_INDEXES;
+        /// All checks needed to define a valid tree sequence.
+        /// Implies all of the above.
+        const CHECK_TREES = ll_bindings::TSK_CHECK_TREES;
+        /// Do not check integrity of references to populations.
+        const NO_CHECK_POPULATION_REFS = ll_bindings::TSK_NO_CHECK_POPULATION_REFS;
+    }
+}
+
+bitflags! {
+    /// Modify behavior of tree sequence statistics,
+    /// such as [`crate::TreeSequence::diversity_with_options`].
+    ///
+    /// At most one of `SITE`, `BRANCH`, or `NODE` may be set.
+    /// If none are set, `SITE` is used.
+    ///
+    /// For the common cases, see [`crate::StatisticsMode`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::StatisticsOptions as SO;
+    /// let flags = SO::BRANCH | SO::SPAN_NORMALISE;
+    /// assert!(flags.contains(SO::BRANCH));
+    /// assert!(!flags.contains(SO::POLARISED));
+    /// ```
+    #[derive(Default)]
+    pub struct StatisticsOptions : tsk_flags_t {
+        /// Default behavior.
+        const NONE = 0;
+        /// Statistics based on mutations at sites.
+        const SITE = ll_bindings::TSK_STAT_SITE;
+        /// Statistics based on branch lengths.
+        const BRANCH = ll_bindings::TSK_STAT_BRANCH;
+        /// Statistics reported for each node.
+        const NODE = ll_bindings::TSK_STAT_NODE;
+        /// Do not sum over the ancestral and derived states.
+        const POLARISED = ll_bindings::TSK_STAT_POLARISED;
+        /// Divide the statistic in each window by the window's span.
+        const SPAN_NORMALISE = ll_bindings::TSK_STAT_SPAN_NORMALISE;
+    }
+}
+
 bitflags! {
     /// Specify the behavior of iterating over [`Tree`] objects.
     /// See [`TreeSequence::tree_iterator`].
+    ///
+    /// [`TreeFlags::SAMPLE_LISTS`] and [`TreeFlags::NO_SAMPLE_COUNTS`]
+    /// are mutually exclusive.
     #[derive(Default)]
     pub struct TreeFlags: tsk_flags_t {
         /// Default behavior.
@@ -147,3 +261,61 @@ bitflags! {
         const BUILD_INDEXES = ll_bindings::TSK_BUILD_INDEXES;
     }
 }
+
+bitflags! {
+    /// Modify behavior of [`crate::TableCollection::compute_mutation_times`].
+    ///
+    /// The `C API` currently defines no options, so only
+    /// the default is available.
+    #[derive(Default)]
+    pub struct MutationTimeOptions : tsk_flags_t {
+        /// Default behavior
+        const NONE = 0;
+    }
+}
+
+bitflags! {
+    /// Node flags.
+    ///
+    /// Used when adding rows to the node table,
+    /// such as via [`crate::TableCollection::add_node`].
+    ///
+    /// The 16 least significant bits are reserved for use by `tskit`.
+    /// The remaining bits, [`NodeFlags::USER_BITS`], are available
+    /// for use by client code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let flags = tskit::NodeFlags::IS_SAMPLE | tskit::NodeFlags::from(1 << 16);
+    /// let id = tables.add_node(flags, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// let raw: tskit::tsk_flags_t = flags.into();
+    /// assert_eq!(raw, tskit::TSK_NODE_IS_SAMPLE | (1 << 16));
+    /// ```
+    #[derive(Default)]
+    pub struct NodeFlags : tsk_flags_t {
+        /// Default behavior
+        const NONE = 0;
+        /// The node is a sample.
+        const IS_SAMPLE = ll_bindings::TSK_NODE_IS_SAMPLE;
+        /// The bits available for use by client code.
+        const USER_BITS = 0xFFFF_0000;
+    }
+}
+
+impl From<tsk_flags_t> for NodeFlags {
+    /// All bits are retained, including ones reserved by `tskit`
+    /// that do not have a named constant.
+    fn from(value: tsk_flags_t) -> Self {
+        // SAFETY: the value is only ever used as a bit mask,
+        // so unknown bits are harmless.
+        unsafe { Self::from_bits_unchecked(value) }
+    }
+}
+
+impl From<NodeFlags> for tsk_flags_t {
+    fn from(value: NodeFlags) -> Self {
+        value.bits()
+    }
+}
@@ -0,0 +1,192 @@
+use crate::bindings as ll_bindings;
+use crate::error::TskitError;
+use crate::ffi::WrapTskitType;
+use crate::TskitTypeAccess;
+use crate::{tsk_id_t, TreeSequence};
+use ll_bindings::tsk_vargen_free;
+
+/// Genotypes at all sites of a [`TreeSequence`].
+///
+/// The data are stored by site.  For each site,
+/// there is one value per sample, giving the index
+/// of that sample's allele.  The ancestral state
+/// is allele 0.  Missing data are encoded as -1.
+///
+/// These are not created directly.
+/// Instead, use [`TreeSequence::genotype_matrix`]
+/// or [`TreeSequence::genotype_matrix_for_samples`].
+pub struct GenotypeMatrix {
+    genotypes: Vec<i8>,
+    num_sites: usize,
+    num_samples: usize,
+}
+
+impl GenotypeMatrix {
+    /// Return the number of sites (rows).
+    pub fn num_sites(&self) -> usize {
+        self.num_sites
+    }
+
+    /// Return the number of samples (columns).
+    pub fn num_samples(&self) -> usize {
+        self.num_samples
+    }
+
+    /// Return all genotypes as a slice.
+    ///
+    /// The genotypes for site `i` are in
+    /// `[i * num_samples, (i + 1) * num_samples)`.
+    pub fn as_slice(&self) -> &[i8] {
+        &self.genotypes
+    }
+
+    /// Return the genotypes at site `site`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `site` is out of range.
+    pub fn site(&self, site: usize) -> Result<&[i8], TskitError> {
+        if site >= self.num_sites {
+            return Err(TskitError::IndexError);
+        }
+        let start = site * self.num_samples;
+        Ok(&self.genotypes[start..start + self.num_samples])
+    }
+
+    /// Return the genotype of the sample at column `sample`
+    /// for site `site`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if either index is out of range.
+    pub fn get(&self, site: usize, sample: usize) -> Result<i8, TskitError> {
+        if sample >= self.num_samples {
+            return Err(TskitError::IndexError);
+        }
+        Ok(self.site(site)?[sample])
+    }
+}
+
+struct VariantGenerator {
+    inner: Box<ll_bindings::tsk_vargen_t>,
+}
+
+build_tskit_type!(VariantGenerator, ll_bindings::tsk_vargen_t, tsk_vargen_free);
+
+impl VariantGenerator {
+    // NOTE: the C library does not copy `samples`,
+    // which therefore must outlive the generator.
+    fn new(treeseq: &TreeSequence, samples: Option<&[tsk_id_t]>) -> Result<Self, TskitError> {
+        let mut vargen = Self::wrap();
+        let (samples_ptr, num_samples) = match samples {
+            Some(s) => (s.as_ptr(), s.len()),
+            None => (std::ptr::null(), 0),
+        };
+        let rv = unsafe {
+            ll_bindings::tsk_vargen_init(
+                vargen.as_mut_ptr(),
+                treeseq.as_ptr(),
+                samples_ptr,
+                num_samples as ll_bindings::size_t,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        handle_tsk_return_value!(rv, vargen)
+    }
+}
+
+pub(crate) fn genotype_matrix(
+    treeseq: &TreeSequence,
+    samples: Option<&[tsk_id_t]>,
+) -> Result<GenotypeMatrix, TskitError> {
+    let mut vargen = VariantGenerator::new(treeseq, samples)?;
+    let num_samples = vargen.inner.num_samples as usize;
+    let num_sites = vargen.inner.num_sites as usize;
+    let mut genotypes = Vec::with_capacity(num_samples * num_sites);
+    let mut variant: *mut ll_bindings::tsk_variant_t = std::ptr::null_mut();
+    loop {
+        let rv = unsafe { ll_bindings::tsk_vargen_next(vargen.as_mut_ptr(), &mut variant) };
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+        if rv == 0 {
+            break;
+        }
+        let g = unsafe { std::slice::from_raw_parts((*variant).genotypes.i8, num_samples) };
+        genotypes.extend_from_slice(g);
+    }
+    Ok(GenotypeMatrix {
+        genotypes,
+        num_sites,
+        num_samples,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_fixtures::make_small_table_collection_two_trees;
+    use crate::*;
+
+    fn make_treeseq_with_mutations() -> TreeSequence {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(100., Some(b"0")).unwrap();
+        tables.add_site(600., Some(b"0")).unwrap();
+        tables
+            .add_mutation(0, 4, TSK_NULL, 0.5, Some(b"1"))
+            .unwrap();
+        tables
+            .add_mutation(1, 1, TSK_NULL, 1.5, Some(b"1"))
+            .unwrap();
+        tables.tree_sequence(TreeSequenceFlags::default()).unwrap()
+    }
+
+    #[test]
+    fn test_genotype_matrix() {
+        let ts = make_treeseq_with_mutations();
+        let g = ts.genotype_matrix().unwrap();
+        assert_eq!(g.num_sites(), 2);
+        assert_eq!(g.num_samples(), 4);
+        // samples are nodes 2, 3, 4, 5
+        assert_eq!(g.site(0).unwrap(), &[0, 0, 1, 0]);
+        assert_eq!(g.site(1).unwrap(), &[1, 0, 1, 1]);
+        assert!(g.site(2).is_err());
+        assert!(g.get(0, 4).is_err());
+    }
+
+    #[test]
+    fn test_genotype_matrix_for_samples() {
+        let ts = make_treeseq_with_mutations();
+        let full = ts.genotype_matrix().unwrap();
+        let subset = [5, 2];
+        let g = ts.genotype_matrix_for_samples(&subset).unwrap();
+        assert_eq!(g.num_sites(), full.num_sites());
+        assert_eq!(g.num_samples(), subset.len());
+        for site in 0..g.num_sites() {
+            for (i, s) in subset.iter().enumerate() {
+                let column = ts.sample_nodes().iter().position(|x| x == s).unwrap();
+                assert_eq!(g.get(site, i).unwrap(), full.get(site, column).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_genotype_matrix_for_non_samples() {
+        let ts = make_treeseq_with_mutations();
+        assert!(matches!(
+            ts.genotype_matrix_for_samples(&[0, 2]),
+            Err(TskitError::ValueError {
+                got: _,
+                expected: _
+            })
+        ));
+        assert!(matches!(
+            ts.genotype_matrix_for_samples(&[2, 100]),
+            Err(TskitError::IndexError)
+        ));
+        assert!(matches!(
+            ts.genotype_matrix_for_samples(&[2, 2]),
+            Err(TskitError::ErrorCode { code: _ })
+        ));
+    }
+}
@@ -66,6 +66,27 @@ pub type IndividualTableRefIterator<'a> =
     crate::table_iterator::TableIterator<&'a IndividualTable<'a>>;
 pub type IndividualTableIterator<'a> = crate::table_iterator::TableIterator<IndividualTable<'a>>;
 
+table_iterator_impls!(
+    IndividualTableRefIterator,
+    IndividualTableRow,
+    make_individual_table_row
+);
+
+impl<'a> IntoIterator for &'a IndividualTable<'a> {
+    type Item = IndividualTableRow;
+    type IntoIter = IndividualTableRefIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+table_iterator_impls!(
+    IndividualTableIterator,
+    IndividualTableRow,
+    make_individual_table_row
+);
+
 impl<'a> IndividualTable<'a> {
     pub(crate) fn new_from_table(individuals: &'a ll_bindings::tsk_individual_table_t) -> Self {
         IndividualTable {
@@ -78,6 +99,13 @@ impl<'a> IndividualTable<'a> {
         self.table_.num_rows
     }
 
+    /// Return the metadata schema of the table.
+    ///
+    /// Returns `None` if no schema has been set.
+    pub fn metadata_schema(&self) -> Option<String> {
+        metadata_schema_to_string!(self.table_)
+    }
+
     /// Return the flags for a given row.
     ///
     /// # Errors
@@ -136,7 +164,7 @@ impl<'a> IndividualTable<'a> {
     /// The value of the iterator is [`IndividualTableRow`].
     ///
     pub fn iter(&self) -> IndividualTableRefIterator {
-        crate::table_iterator::make_table_iterator::<&IndividualTable<'a>>(&self)
+        crate::table_iterator::make_table_iterator::<&IndividualTable<'a>>(&self, self.num_rows())
     }
 
     /// Return row `r` of the table.
@@ -0,0 +1,169 @@
+use crate::bindings as ll_bindings;
+use crate::error::TskitError;
+use crate::TskitTypeAccess;
+use crate::{tsk_id_t, tsk_size_t, TreeSequence};
+use ll_bindings::tsk_ld_calc_free;
+
+/// Calculate linkage disequilibrium between pairs of sites.
+///
+/// Wrapper around `tsk_ld_calc_t`.
+///
+/// These are not created directly.
+/// Instead, use [`TreeSequence::ld_calculator`].
+///
+/// # Notes
+///
+/// The C library requires that each site has at most one mutation.
+///
+/// [`LdCalculator::r2`] and [`LdCalculator::r2_array`] take
+/// `&mut self` because the C library updates the calculator's
+/// internal tree as it moves between sites.
+/// Use one calculator per thread.
+pub struct LdCalculator<'a> {
+    inner: Box<ll_bindings::tsk_ld_calc_t>,
+    num_sites: tsk_size_t,
+    marker: std::marker::PhantomData<&'a TreeSequence>,
+}
+
+impl<'a> Drop for LdCalculator<'a> {
+    fn drop(&mut self) {
+        let rv = unsafe { tsk_ld_calc_free(&mut *self.inner) };
+        panic_on_tskit_error!(rv);
+    }
+}
+
+impl<'a> LdCalculator<'a> {
+    pub(crate) fn new(treeseq: &'a TreeSequence) -> Result<Self, TskitError> {
+        let temp: std::mem::MaybeUninit<ll_bindings::tsk_ld_calc_t> =
+            std::mem::MaybeUninit::uninit();
+        let mut ld = Self {
+            inner: unsafe { Box::<ll_bindings::tsk_ld_calc_t>::new(temp.assume_init()) },
+            num_sites: unsafe { (*(*treeseq.as_ptr()).tables).sites.num_rows },
+            marker: std::marker::PhantomData,
+        };
+        let rv = unsafe { ll_bindings::tsk_ld_calc_init(&mut *ld.inner, treeseq.as_ptr()) };
+        handle_tsk_return_value!(rv, ld)
+    }
+
+    fn validate_site(&self, site: tsk_id_t) -> Result<(), TskitError> {
+        if site < 0 || site as tsk_size_t >= self.num_sites {
+            return Err(TskitError::IndexError);
+        }
+        Ok(())
+    }
+
+    /// Return the `r^2` measure of linkage disequilibrium
+    /// between sites `a` and `b`.
+    ///
+    /// Requires `&mut self`: see the notes for [`LdCalculator`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if either site is out of range.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    pub fn r2(&mut self, a: tsk_id_t, b: tsk_id_t) -> Result<f64, TskitError> {
+        self.validate_site(a)?;
+        self.validate_site(b)?;
+        let mut r2 = f64::NAN;
+        let rv = unsafe { ll_bindings::tsk_ld_calc_get_r2(&mut *self.inner, a, b, &mut r2) };
+        handle_tsk_return_value!(rv, r2)
+    }
+
+    /// Return the `r^2` values between site `a` and the sites
+    /// to its right.
+    ///
+    /// Requires `&mut self`: see the notes for [`LdCalculator`].
+    ///
+    /// # Parameters
+    ///
+    /// * `a`: the focal site.
+    /// * `max_sites`: the maximum number of values to calculate.
+    /// * `max_distance`: the maximum distance from the position
+    ///   of `a` to the position of the other sites.
+    ///
+    /// # Returns
+    ///
+    /// The value for site `a + i + 1` is at index `i`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if `a` is out of range.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    pub fn r2_array(
+        &mut self,
+        a: tsk_id_t,
+        max_sites: usize,
+        max_distance: f64,
+    ) -> Result<Vec<f64>, TskitError> {
+        self.validate_site(a)?;
+        let max_sites = std::cmp::min(max_sites, (self.num_sites as tsk_id_t - a - 1) as usize);
+        let mut r2 = vec![f64::NAN; max_sites];
+        let mut num_r2_values: tsk_size_t = 0;
+        let rv = unsafe {
+            ll_bindings::tsk_ld_calc_get_r2_array(
+                &mut *self.inner,
+                a,
+                ll_bindings::TSK_DIR_FORWARD as libc::c_int,
+                max_sites as tsk_size_t,
+                max_distance,
+                r2.as_mut_ptr(),
+                &mut num_r2_values,
+            )
+        };
+        r2.truncate(num_r2_values as usize);
+        handle_tsk_return_value!(rv, r2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_fixtures::make_small_table_collection_two_trees;
+    use crate::*;
+
+    fn make_treeseq() -> TreeSequence {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(100., Some(b"0")).unwrap();
+        tables.add_site(200., Some(b"0")).unwrap();
+        tables.add_site(700., Some(b"0")).unwrap();
+        tables
+            .add_mutation(0, 2, TSK_NULL, 0.5, Some(b"1"))
+            .unwrap();
+        tables
+            .add_mutation(1, 4, TSK_NULL, 0.5, Some(b"1"))
+            .unwrap();
+        tables
+            .add_mutation(2, 1, TSK_NULL, 1.5, Some(b"1"))
+            .unwrap();
+        tables.tree_sequence(TreeSequenceFlags::default()).unwrap()
+    }
+
+    #[test]
+    fn test_r2() {
+        let ts = make_treeseq();
+        let mut ld = ts.ld_calculator().unwrap();
+        for a in 0..3 {
+            assert!((ld.r2(a, a).unwrap() - 1.0).abs() <= 1e-12);
+        }
+        assert!((ld.r2(0, 1).unwrap() - 1. / 9.).abs() <= 1e-12);
+        assert!((ld.r2(0, 2).unwrap() - 1. / 9.).abs() <= 1e-12);
+        for (a, b) in [(-1, 0), (0, 3), (TSK_NULL, TSK_NULL)].iter() {
+            assert!(matches!(ld.r2(*a, *b), Err(TskitError::IndexError)));
+        }
+    }
+
+    #[test]
+    fn test_r2_array() {
+        let ts = make_treeseq();
+        let mut ld = ts.ld_calculator().unwrap();
+        let r2 = ld.r2_array(0, 10, f64::INFINITY).unwrap();
+        assert_eq!(r2.len(), 2);
+        assert!(r2.iter().all(|x| (x - 1. / 9.).abs() <= 1e-12));
+        assert_eq!(ld.r2_array(0, 1, f64::INFINITY).unwrap().len(), 1);
+        assert_eq!(ld.r2_array(0, 10, 150.).unwrap().len(), 1);
+        assert!(ld.r2_array(2, 10, f64::INFINITY).unwrap().is_empty());
+        assert!(matches!(
+            ld.r2_array(3, 10, f64::INFINITY),
+            Err(TskitError::IndexError)
+        ));
+    }
+}
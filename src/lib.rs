@@ -45,6 +45,14 @@
 //!
 //! * `provenance`
 //!     * Enables [`provenance`]
+//! * `json`
+//!     * Enables [`TreeSequence::to_json_summary`]
+//!     * Enables [`NodeTable::metadata_json`]
+//!     * This feature pulls in `serde` and `serde_json`.
+//!       It is named for its output format because the optional
+//!       `serde` dependency already defines a feature called `serde`.
+//!     * `provenance` uses the same dependencies (plus `chrono`),
+//!       but does not enable this feature.
 //!
 //! To add features to your `Cargo.toml` file:
 //!
@@ -65,20 +73,27 @@
 pub mod bindings;
 
 mod _macros; // Starts w/_ to be sorted at front by rustfmt!
+mod edge_differences;
 mod edge_table;
 pub mod error;
 pub mod ffi;
 mod flags;
+mod genotypes;
 mod individual_table;
+mod ld;
 pub mod metadata;
 mod migration_table;
 mod mutation_table;
+mod node_array;
 mod node_table;
 mod population_table;
 pub mod prelude;
+mod site_mutation_index;
 mod site_table;
+mod stats;
 mod table_collection;
 mod table_iterator;
+mod table_views;
 mod traits;
 mod trees;
 pub mod types;
@@ -99,20 +114,34 @@ pub use bindings::tsk_size_t;
 /// "Null" identifier value.
 pub const TSK_NULL: tsk_id_t = -1;
 
+/// Value used to indicate that the time of a mutation is unknown.
+///
+/// This is a `NaN` with a specific bit pattern.
+/// Because `NaN` never compares equal to anything,
+/// use [`MutationTable::time_is_unknown`] to test for it.
+pub const UNKNOWN_TIME: f64 = f64::from_bits(bindings::TSK_UNKNOWN_TIME_HEX);
+
+pub use edge_differences::{EdgeDifferences, EdgeDiffsIterator};
 pub use edge_table::{EdgeTable, EdgeTableRow};
 pub use error::TskitError;
 pub use flags::*;
+pub use genotypes::GenotypeMatrix;
 pub use individual_table::{IndividualTable, IndividualTableRow};
+pub use ld::LdCalculator;
 pub use migration_table::{MigrationTable, MigrationTableRow};
 pub use mutation_table::{MutationTable, MutationTableRow};
+pub use node_array::NodeArray;
 pub use node_table::{NodeTable, NodeTableRow};
 pub use population_table::{PopulationTable, PopulationTableRow};
+pub use site_mutation_index::SiteMutationIndex;
 pub use site_table::{SiteTable, SiteTableRow};
-pub use table_collection::TableCollection;
+pub use stats::StatisticsMode;
+pub use table_collection::{NodeBuilder, SchemaSummary, TableCollection};
+pub use table_views::TableViews;
 pub use traits::NodeListGenerator;
 pub use traits::TableAccess;
 pub use traits::TskitTypeAccess;
-pub use trees::{NodeTraversalOrder, Tree, TreeSequence};
+pub use trees::{NodeTraversalOrder, Tree, TreeInfo, TreeSequence, TreeSnapshot, TreesIterator};
 
 // Optional features
 #[cfg(any(doc, feature = "provenance"))]
@@ -148,6 +148,232 @@ pub(crate) fn char_column_to_vector(
     Ok(Some(buffer))
 }
 
+// Return `true` if `schema` is a JSON object whose "codec" is "json".
+#[cfg(feature = "json")]
+pub(crate) fn schema_has_json_codec(schema: &str) -> bool {
+    match serde_json::from_str::<serde_json::Value>(schema) {
+        Ok(value) => value.get("codec").and_then(|c| c.as_str()) == Some("json"),
+        Err(_) => false,
+    }
+}
+
+pub mod struct_codec {
+    //! Encode and decode metadata using the binary layout
+    //! of the `tskit` `"struct"` metadata codec.
+    //!
+    //! The fields are packed in order, without padding,
+    //! using little-endian byte order.
+    //! This is the layout that `tskit` uses for a schema with
+    //! `"codec": "struct"`, where the `"binaryFormat"` of each
+    //! property is given by [`StructFieldType::binary_format`].
+    //!
+    //! # Examples
+    //!
+    //! ```
+    //! use tskit::metadata::struct_codec::{decode_fields, encode_fields, StructField, StructFieldType};
+    //! use tskit::metadata::{MetadataError, MetadataRoundtrip};
+    //!
+    //! // Matches a schema with the properties
+    //! // {"generation": {"type": "integer", "binaryFormat": "i", "index": 0},
+    //! //  "fitness": {"type": "number", "binaryFormat": "d", "index": 1}}
+    //! struct NodeMetadata {
+    //!     generation: i32,
+    //!     fitness: f64,
+    //! }
+    //!
+    //! impl MetadataRoundtrip for NodeMetadata {
+    //!     fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+    //!         Ok(encode_fields(&[
+    //!             StructField::I32(self.generation),
+    //!             StructField::F64(self.fitness),
+    //!         ]))
+    //!     }
+    //!
+    //!     fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+    //!         let fields = decode_fields(md, &[StructFieldType::I32, StructFieldType::F64])?;
+    //!         match fields[..] {
+    //!             [StructField::I32(generation), StructField::F64(fitness)] => {
+    //!                 Ok(Self { generation, fitness })
+    //!             }
+    //!             _ => unreachable!(),
+    //!         }
+    //!     }
+    //! }
+    //!
+    //! let md = NodeMetadata { generation: 10, fitness: 0.5 };
+    //! let bytes = md.encode().unwrap();
+    //! assert_eq!(bytes.len(), 12);
+    //! let decoded = NodeMetadata::decode(&bytes).unwrap();
+    //! assert_eq!(decoded.generation, 10);
+    //! assert_eq!(decoded.fitness, 0.5);
+    //! ```
+
+    use super::MetadataError;
+    use std::convert::TryInto;
+    use thiserror::Error;
+
+    /// The type of a field in struct-encoded metadata.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum StructFieldType {
+        I8,
+        U8,
+        I16,
+        U16,
+        I32,
+        U32,
+        I64,
+        U64,
+        F32,
+        F64,
+    }
+
+    /// The value of a field in struct-encoded metadata.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum StructField {
+        I8(i8),
+        U8(u8),
+        I16(i16),
+        U16(u16),
+        I32(i32),
+        U32(u32),
+        I64(i64),
+        U64(u64),
+        F32(f32),
+        F64(f64),
+    }
+
+    /// Error returned when decoding struct-encoded metadata.
+    #[derive(Error, Debug)]
+    pub enum StructCodecError {
+        /// The length of the metadata does not match the fields.
+        #[error("expected {expected} bytes of metadata but got {got}")]
+        LengthMismatch { expected: usize, got: usize },
+    }
+
+    impl StructFieldType {
+        /// The `"binaryFormat"` of the field in a `tskit` struct schema.
+        ///
+        /// These are the format characters of the Python
+        /// [`struct`](https://docs.python.org/3/library/struct.html) module.
+        pub fn binary_format(&self) -> &'static str {
+            match self {
+                Self::I8 => "b",
+                Self::U8 => "B",
+                Self::I16 => "h",
+                Self::U16 => "H",
+                Self::I32 => "i",
+                Self::U32 => "I",
+                Self::I64 => "q",
+                Self::U64 => "Q",
+                Self::F32 => "f",
+                Self::F64 => "d",
+            }
+        }
+
+        /// The number of bytes that the field occupies.
+        pub fn size(&self) -> usize {
+            match self {
+                Self::I8 | Self::U8 => 1,
+                Self::I16 | Self::U16 => 2,
+                Self::I32 | Self::U32 | Self::F32 => 4,
+                Self::I64 | Self::U64 | Self::F64 => 8,
+            }
+        }
+
+        fn decode(&self, bytes: &[u8]) -> StructField {
+            // The caller guarantees that bytes.len() == self.size(),
+            // so the conversions cannot fail.
+            match self {
+                Self::I8 => StructField::I8(i8::from_le_bytes(bytes.try_into().unwrap())),
+                Self::U8 => StructField::U8(u8::from_le_bytes(bytes.try_into().unwrap())),
+                Self::I16 => StructField::I16(i16::from_le_bytes(bytes.try_into().unwrap())),
+                Self::U16 => StructField::U16(u16::from_le_bytes(bytes.try_into().unwrap())),
+                Self::I32 => StructField::I32(i32::from_le_bytes(bytes.try_into().unwrap())),
+                Self::U32 => StructField::U32(u32::from_le_bytes(bytes.try_into().unwrap())),
+                Self::I64 => StructField::I64(i64::from_le_bytes(bytes.try_into().unwrap())),
+                Self::U64 => StructField::U64(u64::from_le_bytes(bytes.try_into().unwrap())),
+                Self::F32 => StructField::F32(f32::from_le_bytes(bytes.try_into().unwrap())),
+                Self::F64 => StructField::F64(f64::from_le_bytes(bytes.try_into().unwrap())),
+            }
+        }
+    }
+
+    impl StructField {
+        /// The type of the field.
+        pub fn field_type(&self) -> StructFieldType {
+            match self {
+                Self::I8(_) => StructFieldType::I8,
+                Self::U8(_) => StructFieldType::U8,
+                Self::I16(_) => StructFieldType::I16,
+                Self::U16(_) => StructFieldType::U16,
+                Self::I32(_) => StructFieldType::I32,
+                Self::U32(_) => StructFieldType::U32,
+                Self::I64(_) => StructFieldType::I64,
+                Self::U64(_) => StructFieldType::U64,
+                Self::F32(_) => StructFieldType::F32,
+                Self::F64(_) => StructFieldType::F64,
+            }
+        }
+
+        fn encode_into(&self, buffer: &mut Vec<u8>) {
+            match self {
+                Self::I8(x) => buffer.extend_from_slice(&x.to_le_bytes()),
+                Self::U8(x) => buffer.extend_from_slice(&x.to_le_bytes()),
+                Self::I16(x) => buffer.extend_from_slice(&x.to_le_bytes()),
+                Self::U16(x) => buffer.extend_from_slice(&x.to_le_bytes()),
+                Self::I32(x) => buffer.extend_from_slice(&x.to_le_bytes()),
+                Self::U32(x) => buffer.extend_from_slice(&x.to_le_bytes()),
+                Self::I64(x) => buffer.extend_from_slice(&x.to_le_bytes()),
+                Self::U64(x) => buffer.extend_from_slice(&x.to_le_bytes()),
+                Self::F32(x) => buffer.extend_from_slice(&x.to_le_bytes()),
+                Self::F64(x) => buffer.extend_from_slice(&x.to_le_bytes()),
+            }
+        }
+    }
+
+    /// Encode `fields`, in order, as little-endian bytes without padding.
+    pub fn encode_fields(fields: &[StructField]) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(fields.iter().map(|f| f.field_type().size()).sum());
+        for f in fields {
+            f.encode_into(&mut buffer);
+        }
+        buffer
+    }
+
+    /// Decode `md` into fields of the types given by `types`.
+    ///
+    /// This is the inverse of [`encode_fields`].
+    ///
+    /// # Errors
+    ///
+    /// [`MetadataError::RoundtripError`] containing a
+    /// [`StructCodecError::LengthMismatch`] if the length of `md`
+    /// is not the total size of `types`.
+    pub fn decode_fields(
+        md: &[u8],
+        types: &[StructFieldType],
+    ) -> Result<Vec<StructField>, MetadataError> {
+        let expected = types.iter().map(|t| t.size()).sum();
+        if md.len() != expected {
+            return Err(MetadataError::RoundtripError {
+                value: Box::new(StructCodecError::LengthMismatch {
+                    expected,
+                    got: md.len(),
+                }),
+            });
+        }
+        let mut offset = 0;
+        Ok(types
+            .iter()
+            .map(|t| {
+                let field = t.decode(&md[offset..offset + t.size()]);
+                offset += t.size();
+                field
+            })
+            .collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,6 +429,47 @@ mod tests {
         assert_eq!(f.y, df.y);
     }
 
+    #[test]
+    fn test_struct_codec_matches_hand_written_codec() {
+        use struct_codec::*;
+        let f = F { x: -3, y: 42 };
+        let fields = [StructField::I32(f.x), StructField::U32(f.y)];
+        let types = [StructFieldType::I32, StructFieldType::U32];
+        let encoded = encode_fields(&fields);
+        assert_eq!(encoded, f.encode().unwrap());
+        let df = F::decode(&encoded).unwrap();
+        assert_eq!(df.x, f.x);
+        assert_eq!(df.y, f.y);
+        assert_eq!(decode_fields(&f.encode().unwrap(), &types).unwrap(), fields);
+        assert!(decode_fields(&encoded[1..], &types).is_err());
+        assert!(decode_fields(&encoded, &types[..1]).is_err());
+    }
+
+    #[test]
+    fn test_struct_codec_all_types() {
+        use struct_codec::*;
+        let fields = [
+            StructField::I8(-1),
+            StructField::U8(255),
+            StructField::I16(-300),
+            StructField::U16(60000),
+            StructField::I32(i32::MIN),
+            StructField::U32(u32::MAX),
+            StructField::I64(-5),
+            StructField::U64(u64::MAX),
+            StructField::F32(1.5),
+            StructField::F64(-0.25),
+        ];
+        let types: Vec<StructFieldType> = fields.iter().map(|f| f.field_type()).collect();
+        let formats: String = types.iter().map(|t| t.binary_format()).collect();
+        assert_eq!(formats, "bBhHiIqQfd");
+        let encoded = encode_fields(&fields);
+        assert_eq!(encoded.len(), 1 + 1 + 2 + 2 + 4 + 4 + 8 + 8 + 4 + 8);
+        assert_eq!(&encoded[2..4], &(-300_i16).to_le_bytes());
+        assert_eq!(decode_fields(&encoded, &types).unwrap(), fields);
+        assert!(decode_fields(&[], &[]).unwrap().is_empty());
+    }
+
     #[test]
     fn test_encoded_metadata_roundtrip() {
         let f = F { x: -3, y: 42 };
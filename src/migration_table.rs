@@ -48,25 +48,26 @@ pub type MigrationTableRefIterator<'a> =
     crate::table_iterator::TableIterator<&'a MigrationTable<'a>>;
 pub type MigrationTableIterator<'a> = crate::table_iterator::TableIterator<MigrationTable<'a>>;
 
-impl<'a> Iterator for MigrationTableRefIterator<'a> {
+table_iterator_impls!(
+    MigrationTableRefIterator,
+    MigrationTableRow,
+    make_migration_table_row
+);
+
+impl<'a> IntoIterator for &'a MigrationTable<'a> {
     type Item = MigrationTableRow;
+    type IntoIter = MigrationTableRefIterator<'a>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_migration_table_row(self.table, self.pos);
-        self.pos += 1;
-        rv
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
-impl<'a> Iterator for MigrationTableIterator<'a> {
-    type Item = crate::migration_table::MigrationTableRow;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_migration_table_row(&self.table, self.pos);
-        self.pos += 1;
-        rv
-    }
-}
+table_iterator_impls!(
+    MigrationTableIterator,
+    MigrationTableRow,
+    make_migration_table_row
+);
 
 /// An immutable view of a migration table.
 ///
@@ -87,6 +88,13 @@ impl<'a> MigrationTable<'a> {
         self.table_.num_rows
     }
 
+    /// Return the metadata schema of the table.
+    ///
+    /// Returns `None` if no schema has been set.
+    pub fn metadata_schema(&self) -> Option<String> {
+        metadata_schema_to_string!(self.table_)
+    }
+
     /// Return the left coordinate for a given row.
     ///
     /// # Errors
@@ -157,7 +165,7 @@ impl<'a> MigrationTable<'a> {
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`MigrationTableRow`].
     pub fn iter(&self) -> MigrationTableRefIterator {
-        crate::table_iterator::make_table_iterator::<&MigrationTable<'a>>(&self)
+        crate::table_iterator::make_table_iterator::<&MigrationTable<'a>>(&self, self.num_rows())
     }
 
     /// Return row `r` of the table.
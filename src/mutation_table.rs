@@ -44,26 +44,27 @@ fn make_mutation_table_row(table: &MutationTable, pos: tsk_id_t) -> Option<Mutat
 pub type MutationTableRefIterator<'a> = crate::table_iterator::TableIterator<&'a MutationTable<'a>>;
 pub type MutationTableIterator<'a> = crate::table_iterator::TableIterator<MutationTable<'a>>;
 
-impl<'a> Iterator for MutationTableRefIterator<'a> {
-    type Item = MutationTableRow;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_mutation_table_row(&self.table, self.pos);
-        self.pos += 1;
-        rv
-    }
-}
+table_iterator_impls!(
+    MutationTableRefIterator,
+    MutationTableRow,
+    make_mutation_table_row
+);
 
-impl<'a> Iterator for MutationTableIterator<'a> {
+impl<'a> IntoIterator for &'a MutationTable<'a> {
     type Item = MutationTableRow;
+    type IntoIter = MutationTableRefIterator<'a>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_mutation_table_row(&self.table, self.pos);
-        self.pos += 1;
-        rv
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
+table_iterator_impls!(
+    MutationTableIterator,
+    MutationTableRow,
+    make_mutation_table_row
+);
+
 /// An immutable view of site table.
 ///
 /// These are not created directly.
@@ -83,6 +84,13 @@ impl<'a> MutationTable<'a> {
         self.table_.num_rows
     }
 
+    /// Return the metadata schema of the table.
+    ///
+    /// Returns `None` if no schema has been set.
+    pub fn metadata_schema(&self) -> Option<String> {
+        metadata_schema_to_string!(self.table_)
+    }
+
     /// Return the ``site`` value from row ``row`` of the table.
     ///
     /// # Errors
@@ -123,6 +131,18 @@ impl<'a> MutationTable<'a> {
         unsafe_tsk_column_access!(row, 0, self.num_rows(), self.table_.time)
     }
 
+    /// Return `true` if the ``time`` value from row ``row``
+    /// of the table is [`crate::UNKNOWN_TIME`].
+    ///
+    /// # Errors
+    ///
+    /// Will return [``IndexError``](crate::TskitError::IndexError)
+    /// if ``row`` is out of range.
+    pub fn time_is_unknown(&'a self, row: tsk_id_t) -> Result<bool, TskitError> {
+        let time = self.time(row)?;
+        Ok(time.to_bits() == crate::UNKNOWN_TIME.to_bits())
+    }
+
     /// Get the ``derived_state`` value from row ``row`` of the table.
     ///
     /// # Return
@@ -143,6 +163,49 @@ impl<'a> MutationTable<'a> {
         )
     }
 
+    /// Get the ``derived_state`` value from row ``row`` of the table
+    /// as a string slice.
+    ///
+    /// Unlike [`MutationTable::derived_state`], no data are copied.
+    ///
+    /// # Return
+    ///
+    /// Will return `None` if there is no derived state.
+    ///
+    /// # Errors
+    ///
+    /// * [``IndexError``](crate::TskitError::IndexError)
+    ///   if ``row`` is out of range.
+    /// * [``ValueError``](crate::TskitError::ValueError)
+    ///   if the bytes are not valid UTF-8.
+    pub fn derived_state_str(&self, row: tsk_id_t) -> Result<Option<&'a str>, TskitError> {
+        crate::util::char_column_to_str(
+            self.table_.derived_state,
+            self.table_.derived_state_offset,
+            row,
+            self.table_.num_rows,
+            self.table_.derived_state_length,
+        )
+    }
+
+    /// Get the ``derived_state`` column of the entire table.
+    ///
+    /// The return value contains the packed derived states and
+    /// the offset array.  The derived state of row ``i`` is
+    /// ``states[offsets[i]..offsets[i + 1]]``, which is empty
+    /// if the row has no derived state.
+    /// The offset array has ``num_rows() + 1`` entries.
+    ///
+    /// Unlike [`MutationTable::derived_state`], no data are copied.
+    pub fn derived_states_raw(&self) -> (&'a [u8], &'a [tsk_size_t]) {
+        crate::util::ragged_column_raw(
+            self.table_.derived_state,
+            self.table_.derived_state_offset,
+            self.table_.num_rows,
+            self.table_.derived_state_length,
+        )
+    }
+
     pub fn metadata<T: metadata::MetadataRoundtrip>(
         &'a self,
         row: tsk_id_t,
@@ -151,10 +214,28 @@ impl<'a> MutationTable<'a> {
         decode_metadata_row!(T, buffer)
     }
 
+    /// Decode the metadata of all rows.
+    ///
+    /// # Returns
+    ///
+    /// A vector with one value per row, which is `None`
+    /// for rows without metadata.
+    ///
+    /// # Errors
+    ///
+    /// The first error returned by [`MutationTable::metadata`].
+    pub fn decode_all_metadata<T: metadata::MetadataRoundtrip>(
+        &'a self,
+    ) -> Result<Vec<Option<T>>, TskitError> {
+        (0..self.num_rows() as tsk_id_t)
+            .map(|row| self.metadata::<T>(row))
+            .collect()
+    }
+
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`MutationTableRow`].
     pub fn iter(&self) -> MutationTableRefIterator {
-        crate::table_iterator::make_table_iterator::<&MutationTable<'a>>(&self)
+        crate::table_iterator::make_table_iterator::<&MutationTable<'a>>(&self, self.num_rows())
     }
 
     /// Return row `r` of the table.
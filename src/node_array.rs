@@ -0,0 +1,69 @@
+use crate::{tsk_id_t, TSK_NULL};
+
+/// A read-only array of node ids, indexed by node id.
+///
+/// This is a thin wrapper around slices such as
+/// [`crate::Tree::parent_array`], where missing values
+/// are [`TSK_NULL`](crate::TSK_NULL).
+///
+/// # Examples
+///
+/// ```
+/// let ids = [tskit::TSK_NULL, 0, 0];
+/// let a = tskit::NodeArray::from(&ids[..]);
+/// assert_eq!(a.get(0), None);
+/// assert_eq!(a.get(1), Some(0));
+/// assert_eq!(a.get(3), None);
+/// assert_eq!(a.iter().collect::<Vec<_>>(), vec![(1, 0), (2, 0)]);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct NodeArray<'a> {
+    array: &'a [tsk_id_t],
+}
+
+impl<'a> NodeArray<'a> {
+    /// Return the value for node `u`.
+    ///
+    /// Returns `None` if the value is [`TSK_NULL`](crate::TSK_NULL)
+    /// or if `u` is out of range.
+    pub fn get(&self, u: tsk_id_t) -> Option<tsk_id_t> {
+        if u < 0 {
+            return None;
+        }
+        match self.array.get(u as usize) {
+            Some(&TSK_NULL) | None => None,
+            Some(&v) => Some(v),
+        }
+    }
+
+    /// Return an [`Iterator`] over `(node, value)` pairs,
+    /// skipping nodes whose value is [`TSK_NULL`](crate::TSK_NULL).
+    pub fn iter(&self) -> impl Iterator<Item = (tsk_id_t, tsk_id_t)> + 'a {
+        self.array
+            .iter()
+            .enumerate()
+            .filter(|(_, &v)| v != TSK_NULL)
+            .map(|(u, &v)| (u as tsk_id_t, v))
+    }
+
+    /// Return the length of the array.
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// Return `true` if the array is empty.
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Return the underlying slice.
+    pub fn as_slice(&self) -> &'a [tsk_id_t] {
+        self.array
+    }
+}
+
+impl<'a> From<&'a [tsk_id_t]> for NodeArray<'a> {
+    fn from(array: &'a [tsk_id_t]) -> Self {
+        Self { array }
+    }
+}
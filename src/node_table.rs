@@ -1,6 +1,6 @@
 use crate::bindings as ll_bindings;
 use crate::metadata;
-use crate::{tsk_flags_t, tsk_id_t, TskitError};
+use crate::{tsk_flags_t, tsk_id_t, tsk_size_t, TskitError};
 
 /// Row of a [`NodeTable`]
 pub struct NodeTableRow {
@@ -23,6 +23,32 @@ impl PartialEq for NodeTableRow {
     }
 }
 
+impl NodeTableRow {
+    /// Return `true` if [`crate::TSK_NODE_IS_SAMPLE`] is set in
+    /// the row's `flags`.
+    pub fn is_sample(&self) -> bool {
+        self.flags & crate::TSK_NODE_IS_SAMPLE > 0
+    }
+
+    /// Return the row's `individual`, or `None` if it
+    /// is [`crate::TSK_NULL`].
+    pub fn individual_id(&self) -> Option<tsk_id_t> {
+        match self.individual {
+            crate::TSK_NULL => None,
+            i => Some(i),
+        }
+    }
+
+    /// Return the row's `population`, or `None` if it
+    /// is [`crate::TSK_NULL`].
+    pub fn population_id(&self) -> Option<tsk_id_t> {
+        match self.population {
+            crate::TSK_NULL => None,
+            p => Some(p),
+        }
+    }
+}
+
 fn make_node_table_row(table: &NodeTable, pos: tsk_id_t) -> Option<NodeTableRow> {
     if pos < table.num_rows() as tsk_id_t {
         Some(NodeTableRow {
@@ -41,25 +67,18 @@ fn make_node_table_row(table: &NodeTable, pos: tsk_id_t) -> Option<NodeTableRow>
 pub type NodeTableRefIterator<'a> = crate::table_iterator::TableIterator<&'a NodeTable<'a>>;
 pub type NodeTableIterator<'a> = crate::table_iterator::TableIterator<NodeTable<'a>>;
 
-impl<'a> Iterator for NodeTableRefIterator<'a> {
+table_iterator_impls!(NodeTableRefIterator, NodeTableRow, make_node_table_row);
+
+impl<'a> IntoIterator for &'a NodeTable<'a> {
     type Item = NodeTableRow;
+    type IntoIter = NodeTableRefIterator<'a>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_node_table_row(self.table, self.pos);
-        self.pos += 1;
-        rv
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
-impl<'a> Iterator for NodeTableIterator<'a> {
-    type Item = crate::node_table::NodeTableRow;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_node_table_row(&self.table, self.pos);
-        self.pos += 1;
-        rv
-    }
-}
+table_iterator_impls!(NodeTableIterator, NodeTableRow, make_node_table_row);
 
 /// An immtable view of a node table.
 ///
@@ -80,6 +99,13 @@ impl<'a> NodeTable<'a> {
         self.table_.num_rows
     }
 
+    /// Return the metadata schema of the table.
+    ///
+    /// Returns `None` if no schema has been set.
+    pub fn metadata_schema(&self) -> Option<String> {
+        metadata_schema_to_string!(self.table_)
+    }
+
     /// Return the ``time`` value from row ``row`` of the table.
     ///
     /// # Errors
@@ -90,6 +116,33 @@ impl<'a> NodeTable<'a> {
         unsafe_tsk_column_access!(row, 0, self.num_rows(), self.table_.time)
     }
 
+    /// Return the ``time`` value from row ``row`` of the table
+    /// as an integer.
+    ///
+    /// The time is rounded to the nearest integer, which must be
+    /// within `1e-8` of the stored value.
+    ///
+    /// # Errors
+    ///
+    /// * [``IndexError``](crate::TskitError::IndexError)
+    ///   if ``row`` is out of range.
+    /// * [``ValueError``](crate::TskitError::ValueError)
+    ///   if the time is not integral or does not fit in an `i64`.
+    pub fn time_i64(&'a self, row: tsk_id_t) -> Result<i64, TskitError> {
+        let time = self.time(row)?;
+        let rounded = time.round();
+        if !((rounded - time).abs() <= 1e-8
+            && rounded >= i64::MIN as f64
+            && rounded < i64::MAX as f64)
+        {
+            return Err(TskitError::ValueError {
+                got: format!("{}", time),
+                expected: String::from("an integral time"),
+            });
+        }
+        Ok(rounded as i64)
+    }
+
     /// Return the ``flags`` value from row ``row`` of the table.
     ///
     /// # Errors
@@ -100,11 +153,101 @@ impl<'a> NodeTable<'a> {
         unsafe_tsk_column_access!(row, 0, self.num_rows(), self.table_.flags)
     }
 
+    /// Return `true` if [`crate::TSK_NODE_IS_SAMPLE`] is set in
+    /// the ``flags`` value from row ``row`` of the table.
+    ///
+    /// # Errors
+    ///
+    /// Will return [``IndexError``](crate::TskitError::IndexError)
+    /// if ``row`` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// tables.add_node(0, 1.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// assert!(!tables.nodes().is_sample(0).unwrap());
+    /// assert!(tables.nodes().is_sample(1).unwrap());
+    /// assert!(tables.nodes().row(1).unwrap().is_sample());
+    /// assert!(tables.nodes().is_sample(2).is_err());
+    /// ```
+    pub fn is_sample(&'a self, row: tsk_id_t) -> Result<bool, TskitError> {
+        Ok(self.flags(row)? & crate::TSK_NODE_IS_SAMPLE > 0)
+    }
+
+    /// Return the node flags of all rows as a slice.
+    pub fn flags_array(&self) -> &'a [tsk_flags_t] {
+        unsafe { std::slice::from_raw_parts(self.table_.flags, self.table_.num_rows as usize) }
+    }
+
+    /// Return the node times of all rows as a slice.
+    pub fn time_array(&self) -> &'a [f64] {
+        unsafe { std::slice::from_raw_parts(self.table_.time, self.table_.num_rows as usize) }
+    }
+
+    /// Return the population of all rows as a slice.
+    ///
+    /// Nodes not assigned to a population have a
+    /// value of [`TSK_NULL`](crate::TSK_NULL).
+    pub fn population_array(&self) -> &'a [tsk_id_t] {
+        unsafe { std::slice::from_raw_parts(self.table_.population, self.table_.num_rows as usize) }
+    }
+
+    /// Return the individual of all rows as a slice.
+    ///
+    /// Nodes not assigned to an individual have a
+    /// value of [`TSK_NULL`](crate::TSK_NULL).
+    pub fn individual_array(&self) -> &'a [tsk_id_t] {
+        unsafe { std::slice::from_raw_parts(self.table_.individual, self.table_.num_rows as usize) }
+    }
+
     /// Mutable access to node flags.
     pub fn flags_array_mut(&mut self) -> &mut [tsk_flags_t] {
         unsafe { std::slice::from_raw_parts_mut(self.table_.flags, self.table_.num_rows as usize) }
     }
 
+    /// Set the ``flags`` value of row ``row`` of the table.
+    ///
+    /// # Errors
+    ///
+    /// Will return [``IndexError``](crate::TskitError::IndexError)
+    /// if ``row`` is out of range.
+    pub fn set_flags(&mut self, row: tsk_id_t, flags: tsk_flags_t) -> Result<(), TskitError> {
+        if row < 0 || row as tsk_size_t >= self.table_.num_rows {
+            return Err(TskitError::IndexError {});
+        }
+        self.flags_array_mut()[row as usize] = flags;
+        Ok(())
+    }
+
+    /// Set [`crate::TSK_NODE_IS_SAMPLE`] in the ``flags`` of row ``row``.
+    ///
+    /// Other bits of the ``flags`` are unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Will return [``IndexError``](crate::TskitError::IndexError)
+    /// if ``row`` is out of range.
+    pub fn mark_sample(&mut self, row: tsk_id_t) -> Result<(), TskitError> {
+        let flags = self.flags(row)?;
+        self.set_flags(row, flags | crate::TSK_NODE_IS_SAMPLE)
+    }
+
+    /// Clear [`crate::TSK_NODE_IS_SAMPLE`] from the ``flags`` of row ``row``.
+    ///
+    /// Other bits of the ``flags`` are unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Will return [``IndexError``](crate::TskitError::IndexError)
+    /// if ``row`` is out of range.
+    pub fn unmark_sample(&mut self, row: tsk_id_t) -> Result<(), TskitError> {
+        let flags = self.flags(row)?;
+        self.set_flags(row, flags & !crate::TSK_NODE_IS_SAMPLE)
+    }
+
     /// Mutable access to node times.
     pub fn time_array_mut(&mut self) -> &mut [f64] {
         unsafe { std::slice::from_raw_parts_mut(self.table_.time, self.table_.num_rows as usize) }
@@ -148,10 +291,49 @@ impl<'a> NodeTable<'a> {
         decode_metadata_row!(T, buffer)
     }
 
+    /// Return the metadata of row ``row`` as a JSON string.
+    ///
+    /// The bytes are returned as-is, without decoding them
+    /// into a Rust type.
+    /// Returns `None` if the row has no metadata.
+    ///
+    /// This function is enabled by the `"json"` feature.
+    ///
+    /// # Errors
+    ///
+    /// * [``IndexError``](crate::TskitError::IndexError)
+    ///   if ``row`` is out of range.
+    /// * [``ValueError``](crate::TskitError::ValueError)
+    ///   if the [metadata schema](NodeTable::metadata_schema)
+    ///   does not use the `"json"` codec or if the metadata
+    ///   are not valid UTF-8.
+    #[cfg(feature = "json")]
+    pub fn metadata_json(&'a self, row: tsk_id_t) -> Result<Option<String>, TskitError> {
+        let schema = self.metadata_schema();
+        let uses_json = match &schema {
+            Some(s) => metadata::schema_has_json_codec(s),
+            None => false,
+        };
+        if !uses_json {
+            return Err(TskitError::ValueError {
+                got: format!("{:?}", schema),
+                expected: String::from("a metadata schema with the json codec"),
+            });
+        }
+        let json = crate::util::char_column_to_str(
+            self.table_.metadata,
+            self.table_.metadata_offset,
+            row,
+            self.num_rows(),
+            self.table_.metadata_length,
+        )?;
+        Ok(json.map(String::from))
+    }
+
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`NodeTableRow`].
     pub fn iter(&self) -> NodeTableRefIterator {
-        crate::table_iterator::make_table_iterator::<&NodeTable<'a>>(&self)
+        crate::table_iterator::make_table_iterator::<&NodeTable<'a>>(&self, self.num_rows())
     }
 
     /// Return row `r` of the table.
@@ -173,7 +355,7 @@ impl<'a> NodeTable<'a> {
     pub fn samples_as_vector(&self) -> Vec<tsk_id_t> {
         let mut samples: Vec<tsk_id_t> = vec![];
         for row in self.iter() {
-            if row.flags & crate::TSK_NODE_IS_SAMPLE > 0 {
+            if row.is_sample() {
                 samples.push(row.id);
             }
         }
@@ -32,26 +32,27 @@ pub type PopulationTableRefIterator<'a> =
     crate::table_iterator::TableIterator<&'a PopulationTable<'a>>;
 pub type PopulationTableIterator<'a> = crate::table_iterator::TableIterator<PopulationTable<'a>>;
 
-impl<'a> Iterator for PopulationTableRefIterator<'a> {
-    type Item = PopulationTableRow;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_population_table_row(&self.table, self.pos);
-        self.pos += 1;
-        rv
-    }
-}
+table_iterator_impls!(
+    PopulationTableRefIterator,
+    PopulationTableRow,
+    make_population_table_row
+);
 
-impl<'a> Iterator for PopulationTableIterator<'a> {
+impl<'a> IntoIterator for &'a PopulationTable<'a> {
     type Item = PopulationTableRow;
+    type IntoIter = PopulationTableRefIterator<'a>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_population_table_row(&self.table, self.pos);
-        self.pos += 1;
-        rv
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
+table_iterator_impls!(
+    PopulationTableIterator,
+    PopulationTableRow,
+    make_population_table_row
+);
+
 /// An immutable view of site table.
 ///
 /// These are not created directly.
@@ -71,6 +72,13 @@ impl<'a> PopulationTable<'a> {
         self.table_.num_rows
     }
 
+    /// Return the metadata schema of the table.
+    ///
+    /// Returns `None` if no schema has been set.
+    pub fn metadata_schema(&self) -> Option<String> {
+        metadata_schema_to_string!(self.table_)
+    }
+
     pub fn metadata<T: metadata::MetadataRoundtrip>(
         &'a self,
         row: tsk_id_t,
@@ -79,10 +87,34 @@ impl<'a> PopulationTable<'a> {
         decode_metadata_row!(T, buffer)
     }
 
+    /// Return the id of the first population whose metadata,
+    /// decoded as type `T`, satisfies `pred`.
+    ///
+    /// Rows without metadata are skipped.
+    /// Returns `Ok(None)` if no row matches.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::MetadataError`] if decoding the metadata fails.
+    pub fn find<T, F>(&self, mut pred: F) -> Result<Option<tsk_id_t>, TskitError>
+    where
+        T: metadata::MetadataRoundtrip,
+        F: FnMut(&T) -> bool,
+    {
+        for row in 0..self.num_rows() as tsk_id_t {
+            if let Some(md) = self.metadata::<T>(row)? {
+                if pred(&md) {
+                    return Ok(Some(row));
+                }
+            }
+        }
+        Ok(None)
+    }
+
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`PopulationTableRow`].
     pub fn iter(&self) -> PopulationTableRefIterator {
-        crate::table_iterator::make_table_iterator::<&PopulationTable<'a>>(&self)
+        crate::table_iterator::make_table_iterator::<&PopulationTable<'a>>(&self, self.num_rows())
     }
 
     /// Return row `r` of the table.
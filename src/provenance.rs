@@ -7,6 +7,8 @@
 //!   [`ProvenanceTable`].
 //! * [`ProvenanceTableRow`], which is the value type returned by
 //!   [`ProvenanceTable::iter`].
+//! * JSON provenance records via [`Provenance::add_provenance_json`]
+//!   and [`ProvenanceTableRow::parse_json`].
 //!
 //! See [`Provenance`] for examples.
 
@@ -100,16 +102,72 @@ pub trait Provenance: crate::TableAccess {
     /// # Parameters
     ///
     /// * `record`: the provenance record
-    fn add_provenance(&mut self, record: &str) -> crate::TskReturnValue;
+    fn add_provenance(&mut self, record: &str) -> crate::TskReturnValue {
+        let timestamp = chrono::prelude::Local::now().to_rfc3339();
+        self.add_provenance_with_timestamp(record, &timestamp)
+    }
+    /// Add provenance record with a caller-supplied time stamp.
+    ///
+    /// Useful for reproducible output and for importing records
+    /// from other sources.
+    ///
+    /// # Parameters
+    ///
+    /// * `record`: the provenance record
+    /// * `timestamp`: an [RFC 3339](https://tools.ietf.org/html/rfc3339)
+    ///   time stamp
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`] if `record` is empty or if `timestamp`
+    /// is not a valid RFC 3339 time stamp.
+    fn add_provenance_with_timestamp(
+        &mut self,
+        record: &str,
+        timestamp: &str,
+    ) -> crate::TskReturnValue;
+    /// Add a provenance record by serializing `value` to JSON.
+    ///
+    /// The time stamp is generated as in [`Provenance::add_provenance`].
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`] if `value` cannot be serialized.
+    fn add_provenance_json<T: serde::Serialize>(&mut self, value: &T) -> crate::TskReturnValue {
+        let record = serde_json::to_string(value).map_err(|e| TskitError::ValueError {
+            got: e.to_string(),
+            expected: String::from("JSON-serializable value"),
+        })?;
+        self.add_provenance(&record)
+    }
     /// Return an immutable reference to the table, type [`ProvenanceTable`]
     fn provenances(&self) -> ProvenanceTable;
     /// Return an iterator over the rows of the [`ProvenanceTable`].
     /// See [`ProvenanceTable::iter`] for details.
     fn provenances_iter(&self) -> ProvenanceTableIterator {
-        crate::table_iterator::make_table_iterator::<ProvenanceTable>(self.provenances())
+        crate::table_iterator::make_table_iterator::<ProvenanceTable>(
+            self.provenances(),
+            self.provenances().num_rows(),
+        )
     }
 }
 
+pub(crate) fn validate_provenance(record: &str, timestamp: &str) -> Result<(), TskitError> {
+    if record.is_empty() {
+        return Err(TskitError::ValueError {
+            got: String::from("empty string slice"),
+            expected: String::from("non-empty string slice"),
+        });
+    }
+    if chrono::DateTime::parse_from_rfc3339(timestamp).is_err() {
+        return Err(TskitError::ValueError {
+            got: String::from(timestamp),
+            expected: String::from("RFC 3339 time stamp"),
+        });
+    }
+    Ok(())
+}
+
 #[derive(Eq)]
 /// Row of a [`ProvenanceTable`].
 pub struct ProvenanceTableRow {
@@ -121,6 +179,21 @@ pub struct ProvenanceTableRow {
     pub record: String,
 }
 
+impl ProvenanceTableRow {
+    /// Deserialize the record from JSON.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`] if the record is not valid JSON
+    /// for type `T`.
+    pub fn parse_json<T: serde::de::DeserializeOwned>(&self) -> Result<T, TskitError> {
+        serde_json::from_str(&self.record).map_err(|e| TskitError::ValueError {
+            got: e.to_string(),
+            expected: String::from("JSON record"),
+        })
+    }
+}
+
 impl PartialEq for ProvenanceTableRow {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id && self.timestamp == other.timestamp && self.record == other.record
@@ -152,26 +225,27 @@ fn make_provenance_table_row(table: &ProvenanceTable, pos: tsk_id_t) -> Option<P
 type ProvenanceTableRefIterator<'a> = crate::table_iterator::TableIterator<&'a ProvenanceTable<'a>>;
 type ProvenanceTableIterator<'a> = crate::table_iterator::TableIterator<ProvenanceTable<'a>>;
 
-impl<'a> Iterator for ProvenanceTableRefIterator<'a> {
-    type Item = ProvenanceTableRow;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_provenance_table_row(self.table, self.pos);
-        self.pos += 1;
-        rv
-    }
-}
+table_iterator_impls!(
+    ProvenanceTableRefIterator,
+    ProvenanceTableRow,
+    make_provenance_table_row
+);
 
-impl<'a> Iterator for ProvenanceTableIterator<'a> {
+impl<'a> IntoIterator for &'a ProvenanceTable<'a> {
     type Item = ProvenanceTableRow;
+    type IntoIter = ProvenanceTableRefIterator<'a>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_provenance_table_row(&self.table, self.pos);
-        self.pos += 1;
-        rv
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
+table_iterator_impls!(
+    ProvenanceTableIterator,
+    ProvenanceTableRow,
+    make_provenance_table_row
+);
+
 /// An immutable view of a provenance table.
 ///
 /// These are not created directly.
@@ -181,6 +255,11 @@ impl<'a> Iterator for ProvenanceTableIterator<'a> {
 /// # Notes
 ///
 /// * The type is enabled by the `"provenance"` feature.
+/// * Time stamps and records are decoded as `UTF-8`.
+///   Invalid byte sequences, which can only come from
+///   tables written by other software, are replaced with
+///   [`std::char::REPLACEMENT_CHARACTER`] rather than
+///   raising an error.
 ///
 pub struct ProvenanceTable<'a> {
     table_: &'a ll_bindings::tsk_provenance_table_t,
@@ -263,6 +342,114 @@ impl<'a> ProvenanceTable<'a> {
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`ProvenanceTableRow`].
     pub fn iter(&self) -> ProvenanceTableRefIterator {
-        crate::table_iterator::make_table_iterator::<&ProvenanceTable<'a>>(&self)
+        crate::table_iterator::make_table_iterator::<&ProvenanceTable<'a>>(&self, self.num_rows())
+    }
+}
+
+#[cfg(all(test, feature = "provenance"))]
+mod test_provenances {
+    use super::*;
+    use crate::TskitTypeAccess;
+
+    #[test]
+    fn test_provenance_rows() {
+        let mut tables = crate::TableCollection::new(1.).unwrap();
+        let records = ["first", "zweite \u{00e9}\u{00e8}", "third"];
+        for r in records.iter() {
+            tables.add_provenance(r).unwrap();
+        }
+        let provenances = tables.provenances();
+        assert_eq!(provenances.num_rows(), 3);
+        for (i, row) in provenances.iter().enumerate() {
+            assert_eq!(row.id, i as tsk_id_t);
+            assert_eq!(row.record, records[i]);
+            assert!(chrono::DateTime::parse_from_rfc3339(&row.timestamp).is_ok());
+            assert!(row == provenances.row(i as tsk_id_t).unwrap());
+        }
+        assert!(provenances.row(3).is_err());
+        assert!(provenances.row(-1).is_err());
+    }
+
+    #[test]
+    fn test_clear_provenances() {
+        use crate::TableAccess;
+        let mut tables = crate::TableCollection::new(1.).unwrap();
+        tables
+            .add_node(0, 0.0, crate::TSK_NULL, crate::TSK_NULL)
+            .unwrap();
+        tables.add_provenance("first").unwrap();
+        tables.add_provenance("second").unwrap();
+        assert_eq!(tables.provenances().num_rows(), 2);
+        tables.clear_provenances().unwrap();
+        assert_eq!(tables.provenances().num_rows(), 0);
+        assert_eq!(tables.nodes().num_rows(), 1);
+        tables.add_provenance("third").unwrap();
+        assert_eq!(tables.provenances().record(0).unwrap(), "third");
+    }
+
+    #[test]
+    fn test_add_provenance_with_timestamp() {
+        let mut tables = crate::TableCollection::new(1.).unwrap();
+        let timestamp = "2021-03-04T05:06:07+00:00";
+        tables
+            .add_provenance_with_timestamp("record", timestamp)
+            .unwrap();
+        assert_eq!(tables.provenances().timestamp(0).unwrap(), timestamp);
+        assert_eq!(tables.provenances().record(0).unwrap(), "record");
+        assert!(tables.add_provenance_with_timestamp("", timestamp).is_err());
+        assert!(tables
+            .add_provenance_with_timestamp("record", "yesterday")
+            .is_err());
+        assert_eq!(tables.provenances().num_rows(), 1);
+
+        let mut treeseq = tables
+            .tree_sequence(crate::TreeSequenceFlags::BUILD_INDEXES)
+            .unwrap();
+        treeseq
+            .add_provenance_with_timestamp("another", timestamp)
+            .unwrap();
+        assert_eq!(treeseq.provenances().timestamp(1).unwrap(), timestamp);
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Record {
+        command: String,
+        seed: u64,
+    }
+
+    #[test]
+    fn test_provenance_json_roundtrip() {
+        let mut tables = crate::TableCollection::new(1.).unwrap();
+        let record = Record {
+            command: String::from("simulate"),
+            seed: 42,
+        };
+        tables.add_provenance_json(&record).unwrap();
+        let row = tables.provenances().row(0).unwrap();
+        assert_eq!(row.parse_json::<Record>().unwrap(), record);
+
+        tables.add_provenance("not json").unwrap();
+        let row = tables.provenances().row(1).unwrap();
+        assert!(row.parse_json::<Record>().is_err());
+    }
+
+    #[test]
+    fn test_invalid_utf8_record() {
+        let mut tables = crate::TableCollection::new(1.).unwrap();
+        let timestamp = "2021-01-01T00:00:00+00:00";
+        let record: [u8; 4] = [b'a', 0xff, 0xfe, b'b'];
+        let rv = unsafe {
+            ll_bindings::tsk_provenance_table_add_row(
+                &mut (*tables.as_mut_ptr()).provenances,
+                timestamp.as_ptr() as *const libc::c_char,
+                timestamp.len() as tsk_size_t,
+                record.as_ptr() as *const libc::c_char,
+                record.len() as tsk_size_t,
+            )
+        };
+        assert_eq!(rv, 0);
+        let row = tables.provenances().row(0).unwrap();
+        assert_eq!(row.timestamp, timestamp);
+        assert_eq!(row.record, "a\u{fffd}\u{fffd}b");
     }
 }
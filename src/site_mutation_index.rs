@@ -0,0 +1,68 @@
+use crate::error::TskitError;
+use crate::{tsk_id_t, TreeSequence};
+
+/// The ids of the mutations at each site of a [`TreeSequence`].
+///
+/// These are not created directly.
+/// Instead, use [`TreeSequence::build_site_mutation_index`].
+///
+/// # Examples
+///
+/// ```
+/// let mut tables = tskit::TableCollection::new(100.).unwrap();
+/// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+/// tables.add_site(10., None).unwrap();
+/// tables.add_site(20., None).unwrap();
+/// tables.add_mutation(1, 0, tskit::TSK_NULL, 0.0, None).unwrap();
+/// tables.build_index().unwrap();
+/// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+/// let index = treeseq.build_site_mutation_index();
+/// assert!(index.mutations(0).unwrap().is_empty());
+/// assert_eq!(index.mutations(1).unwrap(), &[0]);
+/// ```
+pub struct SiteMutationIndex<'a> {
+    offsets: Vec<usize>,
+    mutations: Vec<tsk_id_t>,
+    treeseq: std::marker::PhantomData<&'a TreeSequence>,
+}
+
+impl<'a> SiteMutationIndex<'a> {
+    pub(crate) fn new(num_sites: usize, mutation_sites: &[tsk_id_t]) -> Self {
+        let mut offsets = vec![0; num_sites + 1];
+        for &s in mutation_sites {
+            offsets[s as usize + 1] += 1;
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+        let mut next = offsets.clone();
+        let mut mutations = vec![0; mutation_sites.len()];
+        for (m, &s) in mutation_sites.iter().enumerate() {
+            mutations[next[s as usize]] = m as tsk_id_t;
+            next[s as usize] += 1;
+        }
+        Self {
+            offsets,
+            mutations,
+            treeseq: std::marker::PhantomData,
+        }
+    }
+
+    /// Return the ids of the mutations at `site`, in table order.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `site` is out of range.
+    pub fn mutations(&self, site: tsk_id_t) -> Result<&[tsk_id_t], TskitError> {
+        if site < 0 || site as usize >= self.num_sites() {
+            return Err(TskitError::IndexError);
+        }
+        let site = site as usize;
+        Ok(&self.mutations[self.offsets[site]..self.offsets[site + 1]])
+    }
+
+    /// Return the number of sites.
+    pub fn num_sites(&self) -> usize {
+        self.offsets.len() - 1
+    }
+}
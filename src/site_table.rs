@@ -37,26 +37,19 @@ fn make_site_table_row(table: &SiteTable, pos: tsk_id_t) -> Option<SiteTableRow>
 pub type SiteTableRefIterator<'a> = crate::table_iterator::TableIterator<&'a SiteTable<'a>>;
 pub type SiteTableIterator<'a> = crate::table_iterator::TableIterator<SiteTable<'a>>;
 
-impl<'a> Iterator for SiteTableRefIterator<'a> {
-    type Item = SiteTableRow;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_site_table_row(&self.table, self.pos);
-        self.pos += 1;
-        rv
-    }
-}
+table_iterator_impls!(SiteTableRefIterator, SiteTableRow, make_site_table_row);
 
-impl<'a> Iterator for SiteTableIterator<'a> {
+impl<'a> IntoIterator for &'a SiteTable<'a> {
     type Item = SiteTableRow;
+    type IntoIter = SiteTableRefIterator<'a>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let rv = make_site_table_row(&self.table, self.pos);
-        self.pos += 1;
-        rv
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
+table_iterator_impls!(SiteTableIterator, SiteTableRow, make_site_table_row);
+
 /// An immutable view of site table.
 ///
 /// These are not created directly.
@@ -76,6 +69,13 @@ impl<'a> SiteTable<'a> {
         self.table_.num_rows
     }
 
+    /// Return the metadata schema of the table.
+    ///
+    /// Returns `None` if no schema has been set.
+    pub fn metadata_schema(&self) -> Option<String> {
+        metadata_schema_to_string!(self.table_)
+    }
+
     /// Return the ``position`` value from row ``row`` of the table.
     ///
     /// # Errors
@@ -86,6 +86,57 @@ impl<'a> SiteTable<'a> {
         unsafe_tsk_column_access!(row, 0, self.num_rows(), self.table_.position)
     }
 
+    /// Return the positions of all rows as a slice.
+    ///
+    /// For tables forming a valid tree sequence, the positions
+    /// are sorted, allowing binary searches such as
+    /// [`slice::binary_search_by`].
+    pub fn position_array(&self) -> &'a [f64] {
+        unsafe { std::slice::from_raw_parts(self.table_.position, self.table_.num_rows as usize) }
+    }
+
+    /// Return the id of the site at position `pos`.
+    ///
+    /// The search is a binary search of [`SiteTable::position_array`],
+    /// so the sites must be sorted by position.
+    /// If several sites share the position, the first is returned.
+    ///
+    /// Returns `None` if there is no site at `pos`.
+    pub fn site_at_position(&self, pos: f64) -> Option<tsk_id_t> {
+        let positions = self.position_array();
+        let i = positions.partition_point(|&x| x < pos);
+        match positions.get(i) {
+            Some(&x) if x == pos => Some(i as tsk_id_t),
+            _ => None,
+        }
+    }
+
+    /// Return the id of the site closest to position `pos`.
+    ///
+    /// The search is a binary search of [`SiteTable::position_array`],
+    /// so the sites must be sorted by position.
+    /// Ties are broken in favor of the leftmost site.
+    ///
+    /// Returns `None` if the table is empty or `pos` is `NaN`.
+    pub fn nearest_site(&self, pos: f64) -> Option<tsk_id_t> {
+        let positions = self.position_array();
+        if positions.is_empty() || pos.is_nan() {
+            return None;
+        }
+        let i = positions.partition_point(|&x| x < pos);
+        if i == positions.len() {
+            return Some(i as tsk_id_t - 1);
+        }
+        if i > 0 {
+            // The first site at the position to the left.
+            let left = positions[..i].partition_point(|&x| x < positions[i - 1]);
+            if pos - positions[left] <= positions[i] - pos {
+                return Some(left as tsk_id_t);
+            }
+        }
+        Some(i as tsk_id_t)
+    }
+
     /// Get the ``ancestral_state`` value from row ``row`` of the table.
     ///
     /// # Return
@@ -106,6 +157,49 @@ impl<'a> SiteTable<'a> {
         )
     }
 
+    /// Get the ``ancestral_state`` value from row ``row`` of the table
+    /// as a string slice.
+    ///
+    /// Unlike [`SiteTable::ancestral_state`], no data are copied.
+    ///
+    /// # Return
+    ///
+    /// Will return `None` if there is no ancestral state.
+    ///
+    /// # Errors
+    ///
+    /// * [``IndexError``](crate::TskitError::IndexError)
+    ///   if ``row`` is out of range.
+    /// * [``ValueError``](crate::TskitError::ValueError)
+    ///   if the bytes are not valid UTF-8.
+    pub fn ancestral_state_str(&self, row: tsk_id_t) -> Result<Option<&'a str>, TskitError> {
+        crate::util::char_column_to_str(
+            self.table_.ancestral_state,
+            self.table_.ancestral_state_offset,
+            row,
+            self.table_.num_rows,
+            self.table_.ancestral_state_length,
+        )
+    }
+
+    /// Get the ``ancestral_state`` column of the entire table.
+    ///
+    /// The return value contains the packed ancestral states and
+    /// the offset array.  The ancestral state of row ``i`` is
+    /// ``states[offsets[i]..offsets[i + 1]]``, which is empty
+    /// if the row has no ancestral state.
+    /// The offset array has ``num_rows() + 1`` entries.
+    ///
+    /// Unlike [`SiteTable::ancestral_state`], no data are copied.
+    pub fn ancestral_states_raw(&self) -> (&'a [u8], &'a [tsk_size_t]) {
+        crate::util::ragged_column_raw(
+            self.table_.ancestral_state,
+            self.table_.ancestral_state_offset,
+            self.table_.num_rows,
+            self.table_.ancestral_state_length,
+        )
+    }
+
     pub fn metadata<T: metadata::MetadataRoundtrip>(
         &'a self,
         row: tsk_id_t,
@@ -117,7 +211,7 @@ impl<'a> SiteTable<'a> {
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`SiteTableRow`].
     pub fn iter(&self) -> SiteTableRefIterator {
-        crate::table_iterator::make_table_iterator::<&SiteTable<'a>>(&self)
+        crate::table_iterator::make_table_iterator::<&SiteTable<'a>>(&self, self.num_rows())
     }
 
     /// Return row `r` of the table.
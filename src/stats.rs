@@ -0,0 +1,217 @@
+use crate::bindings as ll_bindings;
+use crate::error::TskitError;
+use crate::StatisticsOptions;
+use crate::TableAccess;
+use crate::TskitTypeAccess;
+use crate::{tsk_flags_t, tsk_id_t, tsk_size_t, TreeSequence};
+
+/// The mode in which a tree sequence statistic is calculated.
+///
+/// Used by the statistics functions of [`TreeSequence`],
+/// such as [`TreeSequence::diversity`].
+/// For finer control, see [`StatisticsOptions`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatisticsMode {
+    /// Based on mutations at sites.
+    Site,
+    /// Based on branch lengths.
+    Branch,
+    /// Reported separately for each node.
+    Node,
+}
+
+impl From<StatisticsMode> for StatisticsOptions {
+    fn from(mode: StatisticsMode) -> Self {
+        match mode {
+            StatisticsMode::Site => StatisticsOptions::SITE,
+            StatisticsMode::Branch => StatisticsOptions::BRANCH,
+            StatisticsMode::Node => StatisticsOptions::NODE,
+        }
+    }
+}
+
+// Signature shared by the C library's one-way statistics
+// of sample sets, e.g. tsk_treeseq_diversity.
+pub(crate) type OneWaySampleStatFn = unsafe extern "C" fn(
+    *const ll_bindings::tsk_treeseq_t,
+    tsk_size_t,
+    *const tsk_size_t,
+    *const tsk_id_t,
+    tsk_size_t,
+    *const f64,
+    *mut f64,
+    tsk_flags_t,
+) -> libc::c_int;
+
+impl TreeSequence {
+    // Marshal the sample sets and windows, call `stat`, and return
+    // the output, which has one row per window.
+    pub(crate) fn run_windowed_stat(
+        &self,
+        stat: OneWaySampleStatFn,
+        sample_sets: &[&[tsk_id_t]],
+        windows: Option<&[f64]>,
+        options: StatisticsOptions,
+    ) -> Result<Vec<f64>, TskitError> {
+        let sample_set_sizes: Vec<tsk_size_t> =
+            sample_sets.iter().map(|s| s.len() as tsk_size_t).collect();
+        let flattened: Vec<tsk_id_t> = sample_sets.iter().flat_map(|s| s.iter().copied()).collect();
+        let (windows_ptr, num_windows) = match windows {
+            Some(w) => {
+                if w.len() < 2 {
+                    return Err(TskitError::ValueError {
+                        got: format!("{} window breakpoints", w.len()),
+                        expected: String::from("at least 2 window breakpoints"),
+                    });
+                }
+                (w.as_ptr(), w.len() - 1)
+            }
+            None => (std::ptr::null(), 1),
+        };
+        let values_per_window = match options.contains(StatisticsOptions::NODE) {
+            true => self.nodes().num_rows() as usize * sample_sets.len(),
+            false => sample_sets.len(),
+        };
+        let mut result = vec![f64::NAN; num_windows * values_per_window];
+        let rv = unsafe {
+            stat(
+                self.as_ptr(),
+                sample_sets.len() as tsk_size_t,
+                sample_set_sizes.as_ptr(),
+                flattened.as_ptr(),
+                num_windows as tsk_size_t,
+                windows_ptr,
+                result.as_mut_ptr(),
+                options.bits(),
+            )
+        };
+        handle_tsk_return_value!(rv, result)
+    }
+}
+
+// Tajima's D from the (unnormalised) diversity and number of
+// segregating sites of a sample set of size n.
+pub(crate) fn tajimas_d(n: usize, pi: f64, segsites: f64) -> f64 {
+    if n < 2 || segsites == 0.0 {
+        return f64::NAN;
+    }
+    let nf = n as f64;
+    let a1: f64 = (1..n).map(|i| 1.0 / i as f64).sum();
+    let a2: f64 = (1..n).map(|i| 1.0 / (i as f64 * i as f64)).sum();
+    let b1 = (nf + 1.0) / (3.0 * (nf - 1.0));
+    let b2 = 2.0 * (nf * nf + nf + 3.0) / (9.0 * nf * (nf - 1.0));
+    let c1 = b1 - 1.0 / a1;
+    let c2 = b2 - (nf + 2.0) / (a1 * nf) + a2 / (a1 * a1);
+    let e1 = c1 / a1;
+    let e2 = c2 / (a1 * a1 + a2);
+    (pi - segsites / a1) / (e1 * segsites + e2 * segsites * (segsites - 1.0)).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_fixtures::{
+        make_small_table_collection_two_trees, treeseq_from_small_table_collection_two_trees,
+    };
+    use crate::TSK_NULL;
+
+    #[test]
+    fn test_diversity_span_normalise() {
+        let ts = treeseq_from_small_table_collection_two_trees();
+        let samples = ts.sample_nodes();
+        let windows = [0., 250., 1000.];
+        let normalised = ts
+            .diversity(&[samples], Some(&windows), StatisticsMode::Branch)
+            .unwrap();
+        let raw = ts
+            .diversity_with_options(&[samples], Some(&windows), StatisticsOptions::BRANCH)
+            .unwrap();
+        assert_eq!(normalised.len(), 2);
+        assert_eq!(raw.len(), 2);
+        for (i, (n, r)) in normalised.iter().zip(raw.iter()).enumerate() {
+            assert!(n.is_finite());
+            assert!((n * (windows[i + 1] - windows[i]) - r).abs() <= 1e-9 * r.abs());
+        }
+    }
+
+    #[test]
+    fn test_diversity_node_mode() {
+        let ts = treeseq_from_small_table_collection_two_trees();
+        let samples = ts.sample_nodes();
+        let d = ts
+            .diversity(&[samples, &samples[0..2]], None, StatisticsMode::Node)
+            .unwrap();
+        assert_eq!(d.len(), 2 * ts.nodes().num_rows() as usize);
+    }
+
+    #[test]
+    fn test_segregating_sites() {
+        let ts = treeseq_from_small_table_collection_two_trees();
+        let samples = ts.sample_nodes();
+        let windows = [0., 500., 1000.];
+        let s = ts
+            .segregating_sites(&[samples], Some(&windows), StatisticsMode::Branch)
+            .unwrap();
+        assert_eq!(s.len(), 2);
+        assert!(s.iter().all(|x| x.is_finite() && *x > 0.0));
+
+        // No sites, so no segregating sites.
+        let s = ts
+            .segregating_sites(&[samples], None, StatisticsMode::Site)
+            .unwrap();
+        assert_eq!(s, vec![0.0]);
+    }
+
+    #[test]
+    fn test_tajimas_d() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(100., Some(b"0")).unwrap();
+        tables.add_site(200., Some(b"0")).unwrap();
+        tables.add_site(700., Some(b"0")).unwrap();
+        tables
+            .add_mutation(0, 2, TSK_NULL, 0.5, Some(b"1"))
+            .unwrap();
+        tables
+            .add_mutation(1, 4, TSK_NULL, 0.5, Some(b"1"))
+            .unwrap();
+        tables
+            .add_mutation(2, 1, TSK_NULL, 1.5, Some(b"1"))
+            .unwrap();
+        let ts = tables
+            .tree_sequence(crate::TreeSequenceFlags::default())
+            .unwrap();
+        let samples = ts.sample_nodes();
+
+        let d = ts.Tajimas_D(&[samples], None).unwrap();
+        assert_eq!(d.len(), 1);
+        // All three sites have pi = 0.5, so pi = 1.5 and S = 3.
+        // With n = 4, D = -0.754451..., worked out by hand.
+        assert!((d[0] - -0.7544510776527732).abs() <= 1e-12);
+        // An excess of low-frequency variants.
+        assert!(d[0].is_finite());
+        assert!(d[0] < 0.0);
+
+        let d = ts
+            .Tajimas_D(&[samples, &samples[0..1]], Some(&[0., 500., 1000.]))
+            .unwrap();
+        assert_eq!(d.len(), 4);
+        assert!(d[0].is_finite());
+        assert!(d[1].is_nan());
+        // Only one site, with pi = 0.5, in the second window,
+        // giving D = -sqrt(6) / 4.
+        assert!((d[2] - -(6.0_f64.sqrt()) / 4.0).abs() <= 1e-12);
+        assert!(d[3].is_nan());
+    }
+
+    #[test]
+    fn test_diversity_bad_windows() {
+        let ts = treeseq_from_small_table_collection_two_trees();
+        let samples = ts.sample_nodes();
+        assert!(ts
+            .diversity(&[samples], Some(&[0.]), StatisticsMode::Site)
+            .is_err());
+        assert!(ts
+            .diversity(&[samples], Some(&[0., 10.]), StatisticsMode::Site)
+            .is_err());
+    }
+}
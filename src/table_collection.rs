@@ -7,6 +7,8 @@ use crate::EdgeTable;
 use crate::IndividualTable;
 use crate::MigrationTable;
 use crate::MutationTable;
+use crate::MutationTimeOptions;
+use crate::NodeFlags;
 use crate::NodeTable;
 use crate::PopulationTable;
 use crate::SimplificationOptions;
@@ -14,6 +16,7 @@ use crate::SiteTable;
 use crate::TableAccess;
 use crate::TableClearOptions;
 use crate::TableEqualityOptions;
+use crate::TableIntegrityCheckOptions;
 use crate::TableOutputOptions;
 use crate::TableSortOptions;
 use crate::TreeSequenceFlags;
@@ -159,23 +162,125 @@ impl TableCollection {
         handle_tsk_return_value!(rv, tables)
     }
 
+    /// Load a table collection from bytes in the `tskit`
+    /// file format, such as those returned by
+    /// [`TableCollection::dump_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IOError`] if the temporary file used
+    ///   for loading cannot be created or written.
+    /// * [`TskitError::ErrorCode`] if the bytes are not a valid
+    ///   table collection.
+    pub fn load_bytes(bytes: &[u8]) -> Result<Self, TskitError> {
+        let mut tables = TableCollection::new(1.0)?; // Arbitrary sequence_length.
+        let file = TempFile::new()?;
+        file.write_all(bytes)?;
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_loadf(
+                tables.as_mut_ptr(),
+                file.as_ptr(),
+                ll_bindings::TSK_NO_INIT,
+            )
+        };
+        handle_tsk_return_value!(rv, tables)
+    }
+
+    /// Load a table collection from a file and return
+    /// its metadata schemas.
+    ///
+    /// The tables are dropped before returning.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ErrorCode`] if the file cannot be loaded.
+    pub fn peek_metadata_schemas(filename: &str) -> Result<SchemaSummary, TskitError> {
+        let tables = Self::new_from_file(filename)?;
+        Ok(SchemaSummary {
+            top_level: tables.metadata_schema(),
+            individuals: tables.individuals().metadata_schema(),
+            nodes: tables.nodes().metadata_schema(),
+            edges: tables.edges().metadata_schema(),
+            migrations: tables.migrations().metadata_schema(),
+            sites: tables.sites().metadata_schema(),
+            mutations: tables.mutations().metadata_schema(),
+            populations: tables.populations().metadata_schema(),
+        })
+    }
+
     /// Length of the sequence/"genome".
     pub fn sequence_length(&self) -> f64 {
         unsafe { (*self.as_ptr()).sequence_length }
     }
 
+    /// Set the top-level metadata of the table collection.
+    ///
+    /// Any existing metadata are replaced.
+    pub fn set_metadata(&mut self, metadata: &dyn MetadataRoundtrip) -> TskReturnValue {
+        let md = EncodedMetadata::new(Some(metadata))?;
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_set_metadata(self.as_mut_ptr(), md.as_ptr(), md.len())
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Decode the top-level metadata of the table collection.
+    ///
+    /// Returns `Ok(None)` if no metadata have been set.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::MetadataError`] if decoding fails.
+    pub fn metadata<T: MetadataRoundtrip>(&self) -> Result<Option<T>, TskitError> {
+        if self.inner.metadata_length == 0 {
+            return Ok(None);
+        }
+        let buffer = unsafe {
+            std::slice::from_raw_parts(
+                self.inner.metadata as *const u8,
+                self.inner.metadata_length as usize,
+            )
+        };
+        Ok(Some(T::decode(buffer)?))
+    }
+
+    /// Return the top-level metadata schema.
+    ///
+    /// Returns `None` if no schema has been set.
+    pub fn metadata_schema(&self) -> Option<String> {
+        metadata_schema_to_string!(self.inner)
+    }
+
+    /// Set the top-level metadata schema.
+    ///
+    /// The schema is stored as-is and is not validated.
+    pub fn set_metadata_schema(&mut self, schema: &str) -> TskReturnValue {
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_set_metadata_schema(
+                self.as_mut_ptr(),
+                schema.as_ptr() as *const libc::c_char,
+                schema.len() as tsk_size_t,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
     /// Add a row to the edge table
+    ///
+    /// Returns the id of the new row.
     pub fn add_edge(
         &mut self,
         left: f64,
         right: f64,
         parent: tsk_id_t,
         child: tsk_id_t,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         self.add_edge_with_metadata(left, right, parent, child, None)
     }
 
     /// Add a row with metadata to the edge table
+    ///
+    /// Returns the id of the new row.
     pub fn add_edge_with_metadata(
         &mut self,
         left: f64,
@@ -183,7 +288,7 @@ impl TableCollection {
         parent: tsk_id_t,
         child: tsk_id_t,
         metadata: Option<&dyn MetadataRoundtrip>,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         let md = EncodedMetadata::new(metadata)?;
         let rv = unsafe {
             ll_bindings::tsk_edge_table_add_row(
@@ -201,23 +306,27 @@ impl TableCollection {
     }
 
     /// Add a row to the individual table
+    ///
+    /// Returns the id of the new row.
     pub fn add_individual(
         &mut self,
         flags: tsk_flags_t,
         location: &[f64],
         parents: &[tsk_id_t],
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         self.add_individual_with_metadata(flags, location, parents, None)
     }
 
     /// Add a row with metadata to the individual table
+    ///
+    /// Returns the id of the new row.
     pub fn add_individual_with_metadata(
         &mut self,
         flags: tsk_flags_t,
         location: &[f64],
         parents: &[tsk_id_t],
         metadata: Option<&dyn MetadataRoundtrip>,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         let md = EncodedMetadata::new(metadata)?;
         let rv = unsafe {
             ll_bindings::tsk_individual_table_add_row(
@@ -236,6 +345,8 @@ impl TableCollection {
 
     /// Add a row to the migration table
     ///
+    /// Returns the id of the new row.
+    ///
     /// # Warnings
     ///
     /// Migration tables are not currently supported
@@ -246,12 +357,14 @@ impl TableCollection {
         node: tsk_id_t,
         source_dest: (tsk_id_t, tsk_id_t),
         time: f64,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         self.add_migration_with_metadata(span, node, source_dest, time, None)
     }
 
     /// Add a row with metadata to the migration table
     ///
+    /// Returns the id of the new row.
+    ///
     /// # Warnings
     ///
     /// Migration tables are not currently supported
@@ -263,7 +376,7 @@ impl TableCollection {
         source_dest: (tsk_id_t, tsk_id_t),
         time: f64,
         metadata: Option<&dyn MetadataRoundtrip>,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         let md = EncodedMetadata::new(metadata)?;
         let rv = unsafe {
             ll_bindings::tsk_migration_table_add_row(
@@ -282,30 +395,73 @@ impl TableCollection {
     }
 
     /// Add a row to the node table
+    ///
+    /// Returns the id of the new row.
+    ///
+    /// `flags` may be a [`NodeFlags`](crate::NodeFlags)
+    /// or a raw [`tsk_flags_t`](crate::tsk_flags_t).
     pub fn add_node(
         &mut self,
-        flags: ll_bindings::tsk_flags_t,
+        flags: impl Into<NodeFlags>,
         time: f64,
         population: tsk_id_t,
         individual: tsk_id_t,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         self.add_node_with_metadata(flags, time, population, individual, None)
     }
 
+    /// Return a [`NodeBuilder`] that adds a row to the node table.
+    ///
+    /// Unlike [`TableCollection::add_node`], each column is
+    /// set by name, so arguments cannot be given in the wrong order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let pop = tables.add_population().unwrap();
+    /// let id = tables
+    ///     .add_node_builder()
+    ///     .time(1.0)
+    ///     .population(pop)
+    ///     .sample(true)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(id, 0);
+    /// assert_eq!(tables.nodes().population(id).unwrap(), pop);
+    /// assert_eq!(tables.nodes().individual(id).unwrap(), tskit::TSK_NULL);
+    /// assert!(tables.nodes().is_sample(id).unwrap());
+    /// ```
+    pub fn add_node_builder(&mut self) -> NodeBuilder<'_> {
+        NodeBuilder {
+            tables: self,
+            flags: 0,
+            time: 0.0,
+            population: TSK_NULL,
+            individual: TSK_NULL,
+            metadata: None,
+        }
+    }
+
     /// Add a row with metadata to the node table
+    ///
+    /// Returns the id of the new row.
+    ///
+    /// See [`TableCollection::add_node`] for the types accepted for `flags`.
     pub fn add_node_with_metadata(
         &mut self,
-        flags: ll_bindings::tsk_flags_t,
+        flags: impl Into<NodeFlags>,
         time: f64,
         population: tsk_id_t,
         individual: tsk_id_t,
         metadata: Option<&dyn MetadataRoundtrip>,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         let md = EncodedMetadata::new(metadata)?;
         let rv = unsafe {
             ll_bindings::tsk_node_table_add_row(
                 &mut (*self.as_mut_ptr()).nodes,
-                flags,
+                flags.into().bits(),
                 time,
                 population,
                 individual,
@@ -318,17 +474,25 @@ impl TableCollection {
     }
 
     /// Add a row to the site table
-    pub fn add_site(&mut self, position: f64, ancestral_state: Option<&[u8]>) -> TskReturnValue {
+    ///
+    /// Returns the id of the new row.
+    pub fn add_site(
+        &mut self,
+        position: f64,
+        ancestral_state: Option<&[u8]>,
+    ) -> Result<tsk_id_t, TskitError> {
         self.add_site_with_metadata(position, ancestral_state, None)
     }
 
     /// Add a row with metadata to the site table
+    ///
+    /// Returns the id of the new row.
     pub fn add_site_with_metadata(
         &mut self,
         position: f64,
         ancestral_state: Option<&[u8]>,
         metadata: Option<&dyn MetadataRoundtrip>,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         let astate = process_state_input!(ancestral_state);
         let md = EncodedMetadata::new(metadata)?;
 
@@ -347,6 +511,8 @@ impl TableCollection {
     }
 
     /// Add a row to the mutation table.
+    ///
+    /// Returns the id of the new row.
     pub fn add_mutation(
         &mut self,
         site: tsk_id_t,
@@ -354,11 +520,35 @@ impl TableCollection {
         parent: tsk_id_t,
         time: f64,
         derived_state: Option<&[u8]>,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         self.add_mutation_with_metadata(site, node, parent, time, derived_state, None)
     }
 
+    /// Add a row to the mutation table.
+    ///
+    /// A `time` of `None` is stored as [`crate::UNKNOWN_TIME`].
+    ///
+    /// Returns the id of the new row.
+    pub fn add_mutation_with_optional_time(
+        &mut self,
+        site: tsk_id_t,
+        node: tsk_id_t,
+        parent: tsk_id_t,
+        time: Option<f64>,
+        derived_state: Option<&[u8]>,
+    ) -> Result<tsk_id_t, TskitError> {
+        self.add_mutation(
+            site,
+            node,
+            parent,
+            time.unwrap_or(crate::UNKNOWN_TIME),
+            derived_state,
+        )
+    }
+
     /// Add a row with metadata to the mutation table.
+    ///
+    /// Returns the id of the new row.
     pub fn add_mutation_with_metadata(
         &mut self,
         site: tsk_id_t,
@@ -367,7 +557,7 @@ impl TableCollection {
         time: f64,
         derived_state: Option<&[u8]>,
         metadata: Option<&dyn MetadataRoundtrip>,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         let dstate = process_state_input!(derived_state);
         let md = EncodedMetadata::new(metadata)?;
 
@@ -389,15 +579,19 @@ impl TableCollection {
     }
 
     /// Add a row to the population_table
-    pub fn add_population(&mut self) -> TskReturnValue {
+    ///
+    /// Returns the id of the new row.
+    pub fn add_population(&mut self) -> Result<tsk_id_t, TskitError> {
         self.add_population_with_metadata(None)
     }
 
     /// Add a row with metadata to the population_table
+    ///
+    /// Returns the id of the new row.
     pub fn add_population_with_metadata(
         &mut self,
         metadata: Option<&dyn MetadataRoundtrip>,
-    ) -> TskReturnValue {
+    ) -> Result<tsk_id_t, TskitError> {
         let md = EncodedMetadata::new(metadata)?;
         let rv = unsafe {
             ll_bindings::tsk_population_table_add_row(
@@ -463,6 +657,33 @@ impl TableCollection {
     /// Sort the tables.  
     /// The [``bookmark``](crate::types::Bookmark) can
     /// be used to affect where sorting starts from for each table.
+    ///
+    /// Only the edge and migration tables may be partially sorted.
+    /// Rows before the bookmarked offsets of these tables are not changed.
+    /// The offsets of the site, mutation, and individual tables must
+    /// either be zero, meaning that the table is sorted, or equal to the
+    /// number of rows, meaning that the table is not sorted.
+    /// (For the site and mutation tables, both conditions must hold together.)
+    /// Other offsets are ignored.
+    ///
+    /// # Examples
+    ///
+    /// Sort only the edges added after a bookmark:
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// for time in [0., 1., 2.].iter() {
+    ///     tables.add_node(0, *time, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// }
+    /// tables.add_edge(0., 10., 1, 0).unwrap();
+    /// let start = tskit::types::Bookmark::new().at_edge(tables.edges().num_rows());
+    /// tables.add_edge(5., 10., 2, 1).unwrap();
+    /// tables.add_edge(0., 5., 2, 1).unwrap();
+    /// tables.sort(&start, tskit::TableSortOptions::default()).unwrap();
+    /// assert_eq!(tables.edges().left(1).unwrap(), 0.);
+    /// assert_eq!(tables.edges().left(2).unwrap(), 5.);
+    /// ```
     pub fn sort(&mut self, start: &Bookmark, options: TableSortOptions) -> TskReturnValue {
         let rv = unsafe {
             ll_bindings::tsk_table_collection_sort(
@@ -482,6 +703,150 @@ impl TableCollection {
         self.sort(&b, options)
     }
 
+    /// Sort the individual table so that parents come
+    /// before their children.
+    ///
+    /// No other tables are sorted, although the individual
+    /// ids in the node table are updated.
+    /// Like [`TableCollection::sort`], the table indexes are dropped.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ErrorCode`] if the tables are invalid or if
+    /// the parents of the individuals contain a cycle.
+    pub fn sort_individuals(&mut self, options: TableSortOptions) -> TskReturnValue {
+        let mut b = Bookmark::new();
+        // Setting the offsets to the number of rows skips
+        // sorting these tables.
+        b.offsets.edges = self.edges().num_rows();
+        b.offsets.migrations = self.migrations().num_rows();
+        b.offsets.sites = self.sites().num_rows();
+        b.offsets.mutations = self.mutations().num_rows();
+        self.sort(&b, options)
+    }
+
+    /// Compute the `parent` column of the mutation table
+    /// from the tree topology.
+    ///
+    /// Any existing values in the `parent` column are overwritten.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ErrorCode`] if the tables are not sorted
+    /// and indexed, or are otherwise invalid.
+    ///
+    /// # Note
+    ///
+    /// The `C API` call behind this takes a `flags` argument
+    /// that is currently unused.  A future release may break `API`
+    /// here if the `C` library is updated to use flags.
+    pub fn compute_mutation_parents(&mut self) -> TskReturnValue {
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_compute_mutation_parents(self.as_mut_ptr(), 0)
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Compute the `time` column of the mutation table
+    /// from the tree topology.
+    ///
+    /// Mutations are spread evenly along the edges above
+    /// their nodes.  A mutation above a root is assigned
+    /// the time of that root.
+    ///
+    /// All existing values in the `time` column are overwritten,
+    /// including known times.
+    /// Unknown times are represented by [`crate::UNKNOWN_TIME`].
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ErrorCode`] if the edges are not indexed
+    /// or if the tables are otherwise invalid.
+    pub fn compute_mutation_times(&mut self, options: MutationTimeOptions) -> TskReturnValue {
+        if !self.is_indexed() {
+            return Err(TskitError::ErrorCode {
+                code: ll_bindings::TSK_ERR_TABLES_NOT_INDEXED,
+            });
+        }
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_compute_mutation_times(
+                self.as_mut_ptr(),
+                std::ptr::null_mut(),
+                options.bits(),
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Set the metadata schema of the node table.
+    ///
+    /// The schema is stored as-is and is not validated.
+    pub fn set_node_metadata_schema(&mut self, schema: &str) -> TskReturnValue {
+        set_table_metadata_schema!(self, nodes, tsk_node_table_set_metadata_schema, schema)
+    }
+
+    /// Set the metadata schema of the edge table.
+    ///
+    /// The schema is stored as-is and is not validated.
+    pub fn set_edge_metadata_schema(&mut self, schema: &str) -> TskReturnValue {
+        set_table_metadata_schema!(self, edges, tsk_edge_table_set_metadata_schema, schema)
+    }
+
+    /// Set the metadata schema of the individual table.
+    ///
+    /// The schema is stored as-is and is not validated.
+    pub fn set_individual_metadata_schema(&mut self, schema: &str) -> TskReturnValue {
+        set_table_metadata_schema!(
+            self,
+            individuals,
+            tsk_individual_table_set_metadata_schema,
+            schema
+        )
+    }
+
+    /// Set the metadata schema of the migration table.
+    ///
+    /// The schema is stored as-is and is not validated.
+    pub fn set_migration_metadata_schema(&mut self, schema: &str) -> TskReturnValue {
+        set_table_metadata_schema!(
+            self,
+            migrations,
+            tsk_migration_table_set_metadata_schema,
+            schema
+        )
+    }
+
+    /// Set the metadata schema of the site table.
+    ///
+    /// The schema is stored as-is and is not validated.
+    pub fn set_site_metadata_schema(&mut self, schema: &str) -> TskReturnValue {
+        set_table_metadata_schema!(self, sites, tsk_site_table_set_metadata_schema, schema)
+    }
+
+    /// Set the metadata schema of the mutation table.
+    ///
+    /// The schema is stored as-is and is not validated.
+    pub fn set_mutation_metadata_schema(&mut self, schema: &str) -> TskReturnValue {
+        set_table_metadata_schema!(
+            self,
+            mutations,
+            tsk_mutation_table_set_metadata_schema,
+            schema
+        )
+    }
+
+    /// Set the metadata schema of the population table.
+    ///
+    /// The schema is stored as-is and is not validated.
+    pub fn set_population_metadata_schema(&mut self, schema: &str) -> TskReturnValue {
+        set_table_metadata_schema!(
+            self,
+            populations,
+            tsk_population_table_set_metadata_schema,
+            schema
+        )
+    }
+
     /// Dump the table collection to file.
     ///
     pub fn dump(&self, filename: &str, options: TableOutputOptions) -> TskReturnValue {
@@ -497,6 +862,40 @@ impl TableCollection {
         handle_tsk_return_value!(rv)
     }
 
+    /// Return the table collection in the `tskit` file format.
+    ///
+    /// The data are written to, and read back from, an anonymous
+    /// temporary file (see `tmpfile(3)`), so no named file is
+    /// created and nothing is left behind.
+    ///
+    /// The bytes are the same as those written by
+    /// [`TableCollection::dump`] and may be loaded via
+    /// [`TableCollection::load_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IOError`] if the temporary file used
+    ///   for writing cannot be created or read.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// let bytes = tables.dump_bytes(tskit::TableOutputOptions::default()).unwrap();
+    /// let loaded = tskit::TableCollection::load_bytes(&bytes).unwrap();
+    /// assert!(tables == loaded);
+    /// ```
+    pub fn dump_bytes(&self, options: TableOutputOptions) -> Result<Vec<u8>, TskitError> {
+        let file = TempFile::new()?;
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_dumpf(self.as_ptr(), file.as_ptr(), options.bits())
+        };
+        check_rv(rv)?;
+        Ok(file.read_all()?)
+    }
+
     /// Clear the contents of all tables.
     /// Does not release memory.
     /// Memory will be released when the object goes out
@@ -508,6 +907,21 @@ impl TableCollection {
         handle_tsk_return_value!(rv)
     }
 
+    /// Clear the contents of the provenance table.
+    ///
+    /// Unlike [`TableCollection::clear`], all other tables
+    /// are left unchanged.
+    ///
+    /// This function is enabled by the `"provenance"` feature.
+    #[cfg(any(doc, feature = "provenance"))]
+    pub fn clear_provenances(&mut self) -> TskReturnValue {
+        let rv = unsafe {
+            ll_bindings::tsk_provenance_table_clear(&mut (*self.as_mut_ptr()).provenances)
+        };
+
+        handle_tsk_return_value!(rv)
+    }
+
     /// Free all memory allocated on the C side.
     /// Not public b/c not very safe.
     #[allow(dead_code)]
@@ -525,6 +939,408 @@ impl TableCollection {
         }
     }
 
+    /// Return `true` if `self` and `other` contain the same data
+    /// after putting copies of both into canonical form.
+    ///
+    /// Unlike [`TableCollection::equals`], this comparison ignores
+    /// the order of rows.  Neither `self` nor `other` is modified.
+    ///
+    /// # Note
+    ///
+    /// Canonical form removes unreferenced sites, populations and
+    /// individuals.  Such rows are therefore ignored by this comparison.
+    /// If either copy cannot be put into canonical form (e.g., due to
+    /// invalid tables), `false` is returned.
+    pub fn semantically_equals(&self, other: &TableCollection) -> bool {
+        let canonical_copy = |tables: &TableCollection| -> Result<TableCollection, TskitError> {
+            let mut copy = tables.deepcopy()?;
+            let rv =
+                unsafe { ll_bindings::tsk_table_collection_canonicalise(copy.as_mut_ptr(), 0) };
+            handle_tsk_return_value!(rv, copy)
+        };
+        match (canonical_copy(self), canonical_copy(other)) {
+            (Ok(a), Ok(b)) => a.equals(&b, TableEqualityOptions::default()),
+            _ => false,
+        }
+    }
+
+    /// Check the integrity of the tables.
+    ///
+    /// Useful for validating tables before calling
+    /// [`TableCollection::tree_sequence`].
+    ///
+    /// # Parameters
+    ///
+    /// * `options`: A [`TableIntegrityCheckOptions`] bit field
+    ///   specifying the checks to perform.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ErrorCode`] describing the first problem found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableIntegrityCheckOptions;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 1.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(0, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// // Edges must be sorted by parent time
+    /// tables.add_edge(50., 100., 0, 1).unwrap();
+    /// tables.add_edge(0., 50., 0, 1).unwrap();
+    /// assert!(tables.check_integrity(TableIntegrityCheckOptions::default()).is_ok());
+    /// assert!(tables
+    ///     .check_integrity(TableIntegrityCheckOptions::CHECK_EDGE_ORDERING)
+    ///     .is_err());
+    /// ```
+    pub fn check_integrity(&self, options: TableIntegrityCheckOptions) -> Result<(), TskitError> {
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_check_integrity(self.as_ptr(), options.bits())
+        };
+        handle_tsk_return_value!(rv, ())
+    }
+
+    /// Return `true` if the edge table is sorted.
+    ///
+    /// Edges must be sorted by the time of the parent node,
+    /// then by parent id, then by child id, and then by
+    /// left coordinate.
+    /// This is the order required to create a
+    /// [`crate::TreeSequence`] and is the order produced
+    /// by [`TableCollection::full_sort`].
+    ///
+    /// # Note
+    ///
+    /// Only the edge ordering is checked.
+    /// Use [`TableCollection::check_integrity`] to validate
+    /// the tables.  In particular, edges whose parent is not
+    /// a valid node id will result in `false`.
+    pub fn edges_are_sorted(&self) -> bool {
+        let edges = &self.inner.edges;
+        let num_edges = edges.num_rows as usize;
+        if num_edges == 0 {
+            return true;
+        }
+        let nodes = &self.inner.nodes;
+        let (left, parent, child, time) = unsafe {
+            (
+                std::slice::from_raw_parts(edges.left, num_edges),
+                std::slice::from_raw_parts(edges.parent, num_edges),
+                std::slice::from_raw_parts(edges.child, num_edges),
+                std::slice::from_raw_parts(nodes.time, nodes.num_rows as usize),
+            )
+        };
+        let mut keys = Vec::with_capacity(num_edges);
+        for i in 0..num_edges {
+            match time.get(parent[i] as usize) {
+                Some(&t) if parent[i] >= 0 => keys.push((t, parent[i], child[i], left[i])),
+                _ => return false,
+            }
+        }
+        keys.windows(2).all(|w| w[0] <= w[1])
+    }
+
+    // Remove all genomic information outside of [left, right).
+    // Edges and migrations are clipped to the interval and sites
+    // outside of it are removed, along with their mutations.
+    // The sequence length is unchanged and the edge index is dropped.
+    // The caller is responsible for validating the interval.
+    // Returns IndexError if a mutation's site or parent is out of range.
+    pub(crate) fn keep_interval(&mut self, left: f64, right: f64) -> Result<(), TskitError> {
+        let source = self.deepcopy()?;
+        let src = source.as_ptr();
+        let dest = self.as_mut_ptr();
+        unsafe {
+            check_rv(ll_bindings::tsk_edge_table_clear(&mut (*dest).edges))?;
+            check_rv(ll_bindings::tsk_migration_table_clear(
+                &mut (*dest).migrations,
+            ))?;
+            check_rv(ll_bindings::tsk_site_table_clear(&mut (*dest).sites))?;
+            check_rv(ll_bindings::tsk_mutation_table_clear(
+                &mut (*dest).mutations,
+            ))?;
+            // Edge coordinates change, so any existing index is stale.
+            check_rv(ll_bindings::tsk_table_collection_drop_index(dest, 0))?;
+
+            let mut edge: ll_bindings::tsk_edge_t = std::mem::zeroed();
+            for i in 0..(*src).edges.num_rows as tsk_id_t {
+                check_rv(ll_bindings::tsk_edge_table_get_row(
+                    &(*src).edges,
+                    i,
+                    &mut edge,
+                ))?;
+                let (l, r) = (f64::max(edge.left, left), f64::min(edge.right, right));
+                if l < r {
+                    check_rv(ll_bindings::tsk_edge_table_add_row(
+                        &mut (*dest).edges,
+                        l,
+                        r,
+                        edge.parent,
+                        edge.child,
+                        edge.metadata,
+                        edge.metadata_length,
+                    ))?;
+                }
+            }
+
+            let mut migration: ll_bindings::tsk_migration_t = std::mem::zeroed();
+            for i in 0..(*src).migrations.num_rows as tsk_id_t {
+                check_rv(ll_bindings::tsk_migration_table_get_row(
+                    &(*src).migrations,
+                    i,
+                    &mut migration,
+                ))?;
+                let (l, r) = (
+                    f64::max(migration.left, left),
+                    f64::min(migration.right, right),
+                );
+                if l < r {
+                    check_rv(ll_bindings::tsk_migration_table_add_row(
+                        &mut (*dest).migrations,
+                        l,
+                        r,
+                        migration.node,
+                        migration.source,
+                        migration.dest,
+                        migration.time,
+                        migration.metadata,
+                        migration.metadata_length,
+                    ))?;
+                }
+            }
+
+            let mut site_map = vec![TSK_NULL; (*src).sites.num_rows as usize];
+            let mut site: ll_bindings::tsk_site_t = std::mem::zeroed();
+            for i in 0..(*src).sites.num_rows as tsk_id_t {
+                check_rv(ll_bindings::tsk_site_table_get_row(
+                    &(*src).sites,
+                    i,
+                    &mut site,
+                ))?;
+                if site.position >= left && site.position < right {
+                    site_map[i as usize] = check_rv(ll_bindings::tsk_site_table_add_row(
+                        &mut (*dest).sites,
+                        site.position,
+                        site.ancestral_state,
+                        site.ancestral_state_length,
+                        site.metadata,
+                        site.metadata_length,
+                    ))?;
+                }
+            }
+
+            let mut mutation_map = vec![TSK_NULL; (*src).mutations.num_rows as usize];
+            let mut mutation: ll_bindings::tsk_mutation_t = std::mem::zeroed();
+            for i in 0..(*src).mutations.num_rows as tsk_id_t {
+                check_rv(ll_bindings::tsk_mutation_table_get_row(
+                    &(*src).mutations,
+                    i,
+                    &mut mutation,
+                ))?;
+                let new_site = match site_map.get(mutation.site as usize) {
+                    Some(&s) => s,
+                    None => return Err(TskitError::IndexError),
+                };
+                if new_site != TSK_NULL {
+                    let parent = match mutation.parent {
+                        TSK_NULL => TSK_NULL,
+                        p => match mutation_map.get(p as usize) {
+                            Some(&m) => m,
+                            None => return Err(TskitError::IndexError),
+                        },
+                    };
+                    mutation_map[i as usize] = check_rv(ll_bindings::tsk_mutation_table_add_row(
+                        &mut (*dest).mutations,
+                        new_site,
+                        mutation.node,
+                        parent,
+                        mutation.time,
+                        mutation.derived_state,
+                        mutation.derived_state_length,
+                        mutation.metadata,
+                        mutation.metadata_length,
+                    ))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Keep only the genomic information in [left, right),
+    // then shift all coordinates so that `left` becomes 0.
+    // The sequence length becomes `right - left`.
+    // The caller is responsible for validating the interval.
+    pub(crate) fn trim_to_interval(&mut self, left: f64, right: f64) -> Result<(), TskitError> {
+        self.keep_interval(left, right)?;
+        let tables = unsafe { &mut *self.as_mut_ptr() };
+        let shift = |column: *mut f64, num_rows: tsk_size_t| {
+            if num_rows > 0 {
+                for x in unsafe { std::slice::from_raw_parts_mut(column, num_rows as usize) } {
+                    *x -= left;
+                }
+            }
+        };
+        shift(tables.edges.left, tables.edges.num_rows);
+        shift(tables.edges.right, tables.edges.num_rows);
+        shift(tables.migrations.left, tables.migrations.num_rows);
+        shift(tables.migrations.right, tables.migrations.num_rows);
+        shift(tables.sites.position, tables.sites.num_rows);
+        tables.sequence_length = right - left;
+        Ok(())
+    }
+
+    /// Append the rows of the node, edge, site, and mutation
+    /// tables of `other` to the tables of `self`.
+    ///
+    /// The rows are copied naively: node ids in the new edge and
+    /// mutation rows are shifted by `node_id_offset`, which is
+    /// usually the number of nodes in `self` before the call.
+    /// Site ids and mutation parents are shifted by the number
+    /// of sites and mutations already in `self`.
+    /// The population and individual of each node are copied
+    /// without modification.
+    /// No other tables are copied and no sorting is done.
+    ///
+    /// The rows are appended to a copy of `self`, which replaces
+    /// `self` only on success.  If an error is returned, `self`
+    /// is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the sequence lengths differ
+    ///   or if `node_id_offset` is negative or greater than the
+    ///   number of nodes in `self`.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut a = tskit::TableCollection::new(100.).unwrap();
+    /// a.add_node(0, 1.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// a.add_node(0, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// a.add_edge(0., 100., 0, 1).unwrap();
+    /// let b = a.deepcopy().unwrap();
+    /// a.extend(&b, 2).unwrap();
+    /// assert_eq!(a.edges().parent(1).unwrap(), 2);
+    /// assert_eq!(a.edges().child(1).unwrap(), 3);
+    /// ```
+    pub fn extend(
+        &mut self,
+        other: &TableCollection,
+        node_id_offset: tsk_id_t,
+    ) -> Result<(), TskitError> {
+        if self.sequence_length() != other.sequence_length() {
+            return Err(TskitError::ValueError {
+                got: format!("sequence length {}", other.sequence_length()),
+                expected: format!("sequence length {}", self.sequence_length()),
+            });
+        }
+        let num_nodes = self.nodes().num_rows() as tsk_id_t;
+        if node_id_offset < 0 || node_id_offset > num_nodes {
+            return Err(TskitError::ValueError {
+                got: format!("{}", node_id_offset),
+                expected: format!("0 <= node_id_offset <= {}", num_nodes),
+            });
+        }
+        let mut tables = self.deepcopy()?;
+        tables.append_rows(other, node_id_offset)?;
+        *self = tables;
+        Ok(())
+    }
+
+    // The work of TableCollection::extend, done in place.
+    fn append_rows(
+        &mut self,
+        other: &TableCollection,
+        node_id_offset: tsk_id_t,
+    ) -> Result<(), TskitError> {
+        let src = other.as_ptr();
+        let dest = self.as_mut_ptr();
+        let shift = |u: tsk_id_t, offset: tsk_id_t| match u {
+            TSK_NULL => TSK_NULL,
+            u => u + offset,
+        };
+        unsafe {
+            let site_offset = (*dest).sites.num_rows as tsk_id_t;
+            let mutation_offset = (*dest).mutations.num_rows as tsk_id_t;
+
+            let mut node: ll_bindings::tsk_node_t = std::mem::zeroed();
+            for i in 0..(*src).nodes.num_rows as tsk_id_t {
+                check_rv(ll_bindings::tsk_node_table_get_row(
+                    &(*src).nodes,
+                    i,
+                    &mut node,
+                ))?;
+                check_rv(ll_bindings::tsk_node_table_add_row(
+                    &mut (*dest).nodes,
+                    node.flags,
+                    node.time,
+                    node.population,
+                    node.individual,
+                    node.metadata,
+                    node.metadata_length,
+                ))?;
+            }
+
+            let mut edge: ll_bindings::tsk_edge_t = std::mem::zeroed();
+            for i in 0..(*src).edges.num_rows as tsk_id_t {
+                check_rv(ll_bindings::tsk_edge_table_get_row(
+                    &(*src).edges,
+                    i,
+                    &mut edge,
+                ))?;
+                check_rv(ll_bindings::tsk_edge_table_add_row(
+                    &mut (*dest).edges,
+                    edge.left,
+                    edge.right,
+                    shift(edge.parent, node_id_offset),
+                    shift(edge.child, node_id_offset),
+                    edge.metadata,
+                    edge.metadata_length,
+                ))?;
+            }
+
+            let mut site: ll_bindings::tsk_site_t = std::mem::zeroed();
+            for i in 0..(*src).sites.num_rows as tsk_id_t {
+                check_rv(ll_bindings::tsk_site_table_get_row(
+                    &(*src).sites,
+                    i,
+                    &mut site,
+                ))?;
+                check_rv(ll_bindings::tsk_site_table_add_row(
+                    &mut (*dest).sites,
+                    site.position,
+                    site.ancestral_state,
+                    site.ancestral_state_length,
+                    site.metadata,
+                    site.metadata_length,
+                ))?;
+            }
+
+            let mut mutation: ll_bindings::tsk_mutation_t = std::mem::zeroed();
+            for i in 0..(*src).mutations.num_rows as tsk_id_t {
+                check_rv(ll_bindings::tsk_mutation_table_get_row(
+                    &(*src).mutations,
+                    i,
+                    &mut mutation,
+                ))?;
+                check_rv(ll_bindings::tsk_mutation_table_add_row(
+                    &mut (*dest).mutations,
+                    shift(mutation.site, site_offset),
+                    shift(mutation.node, node_id_offset),
+                    shift(mutation.parent, mutation_offset),
+                    mutation.time,
+                    mutation.derived_state,
+                    mutation.derived_state_length,
+                    mutation.metadata,
+                    mutation.metadata_length,
+                ))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Return a "deep" copy of the tables.
     pub fn deepcopy(&self) -> Result<TableCollection, TskitError> {
         let mut copy = TableCollection::new(1.)?;
@@ -545,6 +1361,45 @@ impl TableCollection {
         crate::TreeSequence::new(self, flags)
     }
 
+    /// Return a [`crate::TreeSequence`] based on the tables,
+    /// building the edge table indexes first if they are missing.
+    ///
+    /// If the tables are not indexed, the edge ordering is checked
+    /// via [`TableCollection::check_integrity`] before calling
+    /// [`TableCollection::build_index`].
+    /// Indexed tables are passed on to [`TableCollection::tree_sequence`]
+    /// unchanged.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ErrorCode`] describing why the edges
+    ///   are not sorted.
+    /// * Any error returned by [`TableCollection::tree_sequence`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 1.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_edge(0., 100., 0, 1).unwrap();
+    /// assert!(!tables.is_indexed());
+    /// let treeseq = tables
+    ///     .tree_sequence_autoindex(tskit::TreeSequenceFlags::default())
+    ///     .unwrap();
+    /// assert_eq!(treeseq.num_trees(), 1);
+    /// ```
+    pub fn tree_sequence_autoindex(
+        mut self,
+        flags: TreeSequenceFlags,
+    ) -> Result<crate::TreeSequence, TskitError> {
+        if !self.is_indexed() {
+            self.check_integrity(TableIntegrityCheckOptions::CHECK_EDGE_ORDERING)?;
+            self.build_index()?;
+        }
+        self.tree_sequence(flags)
+    }
+
     /// Simplify tables in place.
     ///
     /// # Parameters
@@ -558,12 +1413,19 @@ impl TableCollection {
     ///   in length to the input node table.  For each input node,
     ///   this vector either contains the node's new index or [`TSK_NULL`]
     ///   if the input node is not part of the simplified history.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `samples` contains [`TSK_NULL`]
+    ///   or ids out of range.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
     pub fn simplify(
         &mut self,
         samples: &[tsk_id_t],
         options: SimplificationOptions,
         idmap: bool,
     ) -> Result<Option<Vec<tsk_id_t>>, TskitError> {
+        crate::util::validate_sample_ids(samples, self.nodes().num_rows())?;
         let mut output_node_map: Vec<tsk_id_t> = vec![];
         if idmap {
             output_node_map.resize(self.nodes().num_rows() as usize, TSK_NULL);
@@ -588,6 +1450,263 @@ impl TableCollection {
             }
         )
     }
+
+    /// Map node ids from before simplification to their ids
+    /// after simplification.
+    ///
+    /// # Parameters
+    ///
+    /// * `idmap`: the node id map returned by [`TableCollection::simplify`].
+    /// * `old_samples`: node ids from the input to simplification.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if any value in `old_samples`
+    ///   is out of range of `idmap`.
+    /// * [`TskitError::ValueError`] if any value in `old_samples`
+    ///   maps to [`TSK_NULL`], meaning that the node was removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let idmap = vec![tskit::TSK_NULL, 0, 1];
+    /// let new = tskit::TableCollection::translate_samples(&idmap, &[2, 1]).unwrap();
+    /// assert_eq!(new, vec![1, 0]);
+    /// assert!(tskit::TableCollection::translate_samples(&idmap, &[0]).is_err());
+    /// ```
+    pub fn translate_samples(
+        idmap: &[tsk_id_t],
+        old_samples: &[tsk_id_t],
+    ) -> Result<Vec<tsk_id_t>, TskitError> {
+        old_samples
+            .iter()
+            .map(|&s| {
+                if s < 0 || s as usize >= idmap.len() {
+                    return Err(TskitError::IndexError {});
+                }
+                match idmap[s as usize] {
+                    TSK_NULL => Err(TskitError::ValueError {
+                        got: format!("node {} mapped to TSK_NULL", s),
+                        expected: String::from("a node retained by simplification"),
+                    }),
+                    new => Ok(new),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Builder for adding rows to the node table of a [`TableCollection`].
+///
+/// These are not created directly.
+/// Instead, use [`TableCollection::add_node_builder`].
+///
+/// Unset columns default to `flags = 0`, `time = 0.0`,
+/// and [`TSK_NULL`] for `population` and `individual`.
+pub struct NodeBuilder<'a> {
+    tables: &'a mut TableCollection,
+    flags: tsk_flags_t,
+    time: f64,
+    population: tsk_id_t,
+    individual: tsk_id_t,
+    metadata: Option<&'a dyn MetadataRoundtrip>,
+}
+
+impl<'a> NodeBuilder<'a> {
+    /// Set the node flags, replacing any previous value.
+    ///
+    /// See [`TableCollection::add_node`] for the types accepted for `flags`.
+    pub fn flags(mut self, flags: impl Into<NodeFlags>) -> Self {
+        self.flags = flags.into().bits();
+        self
+    }
+
+    /// Set or unset [`crate::TSK_NODE_IS_SAMPLE`] in the node flags.
+    pub fn sample(mut self, is_sample: bool) -> Self {
+        match is_sample {
+            true => self.flags |= crate::TSK_NODE_IS_SAMPLE,
+            false => self.flags &= !crate::TSK_NODE_IS_SAMPLE,
+        }
+        self
+    }
+
+    /// Set the node time.
+    pub fn time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Set the node population.
+    pub fn population(mut self, population: tsk_id_t) -> Self {
+        self.population = population;
+        self
+    }
+
+    /// Set the node individual.
+    pub fn individual(mut self, individual: tsk_id_t) -> Self {
+        self.individual = individual;
+        self
+    }
+
+    /// Set the node metadata.
+    pub fn metadata(mut self, metadata: &'a dyn MetadataRoundtrip) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Add the row to the node table.
+    ///
+    /// # Returns
+    ///
+    /// The id of the new node.
+    pub fn build(self) -> Result<tsk_id_t, TskitError> {
+        self.tables.add_node_with_metadata(
+            self.flags,
+            self.time,
+            self.population,
+            self.individual,
+            self.metadata,
+        )
+    }
+}
+
+/// The metadata schemas of a [`TableCollection`].
+///
+/// Each value is `None` if no schema is set.
+///
+/// These are not created directly.
+/// Instead, use [`TableCollection::peek_metadata_schemas`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaSummary {
+    /// The top-level schema of the table collection.
+    pub top_level: Option<String>,
+    pub individuals: Option<String>,
+    pub nodes: Option<String>,
+    pub edges: Option<String>,
+    pub migrations: Option<String>,
+    pub sites: Option<String>,
+    pub mutations: Option<String>,
+    pub populations: Option<String>,
+}
+
+// An anonymous temporary file, used to pass
+// the file format to and from the C library.
+// The file is removed when closed.
+struct TempFile(*mut libc::FILE);
+
+impl TempFile {
+    fn new() -> Result<Self, std::io::Error> {
+        let file = unsafe { libc::tmpfile() };
+        if file.is_null() {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Self(file))
+    }
+
+    fn as_ptr(&self) -> *mut ll_bindings::FILE {
+        self.0 as *mut ll_bindings::FILE
+    }
+
+    // Write all of `bytes` and rewind to the start of the file.
+    fn write_all(&self, bytes: &[u8]) -> Result<(), std::io::Error> {
+        let n = unsafe {
+            libc::fwrite(
+                bytes.as_ptr() as *const libc::c_void,
+                1,
+                bytes.len(),
+                self.0,
+            )
+        };
+        if n != bytes.len() || unsafe { libc::fflush(self.0) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        unsafe { libc::rewind(self.0) };
+        Ok(())
+    }
+
+    // Read the entire contents of the file.
+    fn read_all(&self) -> Result<Vec<u8>, std::io::Error> {
+        if unsafe { libc::fflush(self.0) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let len = unsafe { libc::ftell(self.0) };
+        if len < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let mut bytes = vec![0_u8; len as usize];
+        unsafe { libc::rewind(self.0) };
+        let n = unsafe {
+            libc::fread(
+                bytes.as_mut_ptr() as *mut libc::c_void,
+                1,
+                bytes.len(),
+                self.0,
+            )
+        };
+        if n != bytes.len() {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(bytes)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        unsafe { libc::fclose(self.0) };
+    }
+}
+
+// Like handle_tsk_return_value!, but usable as an expression
+// when the C function's return value is needed.
+fn check_rv(rv: i32) -> Result<i32, TskitError> {
+    match rv {
+        x if x < 0 => Err(TskitError::ErrorCode { code: x }),
+        x => Ok(x),
+    }
+}
+
+/// Obtain a copy of the tables of a [`crate::TreeSequence`].
+///
+/// Equivalent to [`crate::TreeSequence::dump_tables`].
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// let mut tables = tskit::TableCollection::new(100.).unwrap();
+/// tables.build_index().unwrap();
+/// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+/// let tables = tskit::TableCollection::try_from(&treeseq).unwrap();
+/// assert_eq!(tables.sequence_length(), 100.);
+/// ```
+impl std::convert::TryFrom<&crate::TreeSequence> for TableCollection {
+    type Error = TskitError;
+
+    fn try_from(treeseq: &crate::TreeSequence) -> Result<Self, Self::Error> {
+        treeseq.dump_tables()
+    }
+}
+
+/// Compare two table collections using [`TableCollection::equals`]
+/// with [`TableEqualityOptions::default`].
+///
+/// All data are compared, including metadata and provenance
+/// records with their timestamps.
+/// For less strict comparisons, use [`TableCollection::equals`].
+///
+/// # Examples
+///
+/// ```
+/// let mut a = tskit::TableCollection::new(100.).unwrap();
+/// let b = tskit::TableCollection::new(100.).unwrap();
+/// assert!(a == b);
+/// a.add_node(0, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+/// assert!(a != b);
+/// ```
+impl PartialEq for TableCollection {
+    fn eq(&self, other: &Self) -> bool {
+        self.equals(other, TableEqualityOptions::default())
+    }
 }
 
 impl TableAccess for TableCollection {
@@ -624,14 +1743,8 @@ impl crate::traits::NodeListGenerator for TableCollection {}
 
 #[cfg(any(doc, feature = "provenance"))]
 impl crate::provenance::Provenance for TableCollection {
-    fn add_provenance(&mut self, record: &str) -> TskReturnValue {
-        if record.is_empty() {
-            return Err(TskitError::ValueError {
-                got: String::from("empty string slice"),
-                expected: String::from("non-empty string slice"),
-            });
-        }
-        let timestamp = chrono::prelude::Local::now().to_rfc3339();
+    fn add_provenance_with_timestamp(&mut self, record: &str, timestamp: &str) -> TskReturnValue {
+        crate::provenance::validate_provenance(record, timestamp)?;
         let rv = unsafe {
             ll_bindings::tsk_provenance_table_add_row(
                 &mut (*self.as_mut_ptr()).provenances,
@@ -683,6 +1796,206 @@ mod test {
         let _ = TableCollection::new(-1.).unwrap();
     }
 
+    #[test]
+    fn test_tree_sequence_autoindex() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, TSK_NULL, TSK_NULL)
+            .unwrap();
+        tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, TSK_NULL, TSK_NULL)
+            .unwrap();
+        tables.add_edge(0., 1000., 0, 1).unwrap();
+        tables.add_edge(0., 1000., 0, 2).unwrap();
+        assert!(!tables.is_indexed());
+        let treeseq = tables
+            .tree_sequence_autoindex(TreeSequenceFlags::default())
+            .unwrap();
+        assert_eq!(treeseq.num_trees(), 1);
+        assert_eq!(treeseq.num_samples(), 2);
+
+        let tables = make_small_table_collection();
+        assert!(tables.is_indexed());
+        assert!(tables
+            .tree_sequence_autoindex(TreeSequenceFlags::default())
+            .is_ok());
+
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, TSK_NULL, TSK_NULL)
+            .unwrap();
+        tables.add_edge(500., 1000., 0, 1).unwrap();
+        tables.add_edge(0., 500., 0, 1).unwrap();
+        assert!(matches!(
+            tables.tree_sequence_autoindex(TreeSequenceFlags::default()),
+            Err(TskitError::ErrorCode { code: _ })
+        ));
+    }
+
+    #[test]
+    fn test_partial_edge_sort() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        for time in [0., 0., 1., 2.].iter() {
+            tables.add_node(0, *time, TSK_NULL, TSK_NULL).unwrap();
+        }
+        // The prefix is deliberately out of order.
+        tables.add_edge(0., 10., 3, 2).unwrap();
+        tables.add_edge(0., 10., 2, 1).unwrap();
+        let start = Bookmark::new().at_edge(tables.edges().num_rows());
+        tables.add_edge(0., 10., 3, 0).unwrap();
+        tables.add_edge(5., 10., 2, 0).unwrap();
+        tables.add_edge(0., 5., 2, 0).unwrap();
+        tables.sort(&start, TableSortOptions::default()).unwrap();
+        let edges = tables.edges();
+        let expected = [(0., 3, 2), (0., 2, 1), (0., 2, 0), (5., 2, 0), (0., 3, 0)];
+        for (i, (left, parent, child)) in expected.iter().enumerate() {
+            let row = i as tsk_id_t;
+            assert_eq!(edges.left(row).unwrap(), *left);
+            assert_eq!(edges.parent(row).unwrap(), *parent);
+            assert_eq!(edges.child(row).unwrap(), *child);
+        }
+    }
+
+    #[test]
+    fn test_sort_individuals() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        // Children are added before their parents.
+        tables.add_individual(0, &[], &[1, 2]).unwrap();
+        tables.add_individual(0, &[], &[2, TSK_NULL]).unwrap();
+        tables.add_individual(0, &[], &[]).unwrap();
+        tables.add_node(0, 0.0, TSK_NULL, 0).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, 1).unwrap();
+        tables.add_node(0, 2.0, TSK_NULL, 2).unwrap();
+        tables.add_edge(0., 1000., 1, 0).unwrap();
+        tables.add_edge(0., 1000., 2, 1).unwrap();
+        let edges = tables.edges().num_rows();
+        assert!(tables
+            .check_integrity(TableIntegrityCheckOptions::CHECK_INDIVIDUAL_ORDERING)
+            .is_err());
+
+        tables
+            .sort_individuals(TableSortOptions::default())
+            .unwrap();
+        tables
+            .check_integrity(TableIntegrityCheckOptions::CHECK_INDIVIDUAL_ORDERING)
+            .unwrap();
+        assert_eq!(tables.edges().num_rows(), edges);
+        let individuals = tables.individuals();
+        assert_eq!(individuals.parents(0).unwrap(), None);
+        assert_eq!(individuals.parents(1).unwrap(), Some(vec![0, TSK_NULL]));
+        assert_eq!(individuals.parents(2).unwrap(), Some(vec![1, 0]));
+        let nodes = tables.nodes();
+        assert_eq!(nodes.individual(0).unwrap(), 2);
+        assert_eq!(nodes.individual(1).unwrap(), 1);
+        assert_eq!(nodes.individual(2).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sort_individuals_with_cycle() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_individual(0, &[], &[1]).unwrap();
+        tables.add_individual(0, &[], &[0]).unwrap();
+        assert!(matches!(
+            tables.sort_individuals(TableSortOptions::default()),
+            Err(TskitError::ErrorCode { code: _ })
+        ));
+    }
+
+    #[test]
+    fn test_compute_mutation_parents() {
+        let mut tables = make_small_table_collection();
+        tables.add_site(10., Some(b"0")).unwrap();
+        tables
+            .add_mutation(0, 0, TSK_NULL, 1.5, Some(b"1"))
+            .unwrap();
+        tables
+            .add_mutation(0, 1, TSK_NULL, 0.5, Some(b"0"))
+            .unwrap();
+        tables.compute_mutation_parents().unwrap();
+        let mutations = tables.mutations();
+        assert_eq!(mutations.parent(0).unwrap(), TSK_NULL);
+        assert_eq!(mutations.parent(1).unwrap(), 0);
+        tables
+            .check_integrity(TableIntegrityCheckOptions::CHECK_MUTATION_ORDERING)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_compute_mutation_parents_without_index() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_edge(0., 1000., 0, 1).unwrap();
+        assert!(matches!(
+            tables.compute_mutation_parents(),
+            Err(TskitError::ErrorCode { code: _ })
+        ));
+    }
+
+    #[test]
+    fn test_compute_mutation_times() {
+        let mut tables = make_small_table_collection();
+        tables.add_site(10., Some(b"0")).unwrap();
+        tables
+            .add_mutation_with_optional_time(0, 1, TSK_NULL, None, Some(b"1"))
+            .unwrap();
+        assert!(tables.mutations().time_is_unknown(0).unwrap());
+        tables
+            .compute_mutation_times(MutationTimeOptions::default())
+            .unwrap();
+        let time = tables.mutations().time(0).unwrap();
+        assert!(time.is_finite());
+        let nodes = tables.nodes();
+        assert!(time > nodes.time(1).unwrap());
+        assert!(time < nodes.time(0).unwrap());
+        tables
+            .check_integrity(TableIntegrityCheckOptions::CHECK_MUTATION_ORDERING)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_compute_mutation_times_without_index() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_edge(0., 1000., 0, 1).unwrap();
+        assert!(matches!(
+            tables.compute_mutation_times(MutationTimeOptions::default()),
+            Err(TskitError::ErrorCode {
+                code: ll_bindings::TSK_ERR_TABLES_NOT_INDEXED
+            })
+        ));
+    }
+
+    #[test]
+    fn test_samples_at_time() {
+        use crate::NodeListGenerator;
+        let mut tables = TableCollection::new(1000.).unwrap();
+        for (flags, time) in [
+            (crate::TSK_NODE_IS_SAMPLE, 0.0),
+            (crate::TSK_NODE_IS_SAMPLE, 10.0),
+            (0, 10.0),
+            (crate::TSK_NODE_IS_SAMPLE, 10.0 + 1e-9),
+            (crate::TSK_NODE_IS_SAMPLE, 20.0),
+            (crate::TSK_NODE_IS_SAMPLE, 0.0),
+        ]
+        .iter()
+        {
+            tables.add_node(*flags, *time, TSK_NULL, TSK_NULL).unwrap();
+        }
+        assert_eq!(tables.samples_at_time(0.0, 0.0), vec![0, 5]);
+        assert_eq!(tables.samples_at_time(10.0, 1e-6), vec![1, 3]);
+        assert_eq!(tables.samples_at_time(10.0, 0.0), vec![1]);
+        assert_eq!(tables.samples_at_time(20.0, 1e-6), vec![4]);
+        assert!(tables.samples_at_time(5.0, 1.0).is_empty());
+
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert_eq!(treeseq.samples_at_time(10.0, 1e-6), vec![1, 3]);
+    }
+
     #[test]
     fn test_add_edges() {
         let mut tables = TableCollection::new(1000.).unwrap();
@@ -696,6 +2009,64 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_add_node_with_node_flags() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables
+            .add_node(crate::NodeFlags::IS_SAMPLE, 0.0, TSK_NULL, TSK_NULL)
+            .unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE | 1 << 16, 0.0, TSK_NULL, TSK_NULL)
+            .unwrap();
+        assert!(tables.nodes().is_sample(0).unwrap());
+        assert_eq!(tables.nodes().flags(0).unwrap(), crate::TSK_NODE_IS_SAMPLE);
+        assert!(!tables.nodes().is_sample(1).unwrap());
+        let flags = crate::NodeFlags::from(tables.nodes().flags(2).unwrap());
+        assert!(flags.contains(crate::NodeFlags::IS_SAMPLE));
+        assert_eq!(
+            flags & crate::NodeFlags::USER_BITS,
+            crate::NodeFlags::from(1 << 16)
+        );
+        let raw: tsk_flags_t = flags.into();
+        assert_eq!(raw, crate::TSK_NODE_IS_SAMPLE | 1 << 16);
+
+        let id = tables
+            .add_node_builder()
+            .flags(crate::NodeFlags::IS_SAMPLE | crate::NodeFlags::from(1 << 17))
+            .build()
+            .unwrap();
+        assert_eq!(
+            tables.nodes().flags(id).unwrap(),
+            crate::TSK_NODE_IS_SAMPLE | 1 << 17
+        );
+        let id = tables.add_node_builder().flags(1 << 18).build().unwrap();
+        assert_eq!(tables.nodes().flags(id).unwrap(), 1 << 18);
+    }
+
+    #[test]
+    fn test_set_node_flags() {
+        let tables = make_small_table_collection();
+        let mut nodes = tables.nodes();
+        nodes.set_flags(0, 1 << 20).unwrap();
+        assert_eq!(nodes.flags(0).unwrap(), 1 << 20);
+        nodes.mark_sample(0).unwrap();
+        assert!(nodes.is_sample(0).unwrap());
+        assert_eq!(nodes.flags(0).unwrap(), crate::TSK_NODE_IS_SAMPLE | 1 << 20);
+        nodes.unmark_sample(0).unwrap();
+        assert!(!nodes.is_sample(0).unwrap());
+        assert_eq!(nodes.flags(0).unwrap(), 1 << 20);
+        for row in [TSK_NULL, 3].iter() {
+            assert!(matches!(
+                nodes.set_flags(*row, 0),
+                Err(TskitError::IndexError)
+            ));
+            assert!(nodes.mark_sample(*row).is_err());
+            assert!(nodes.unmark_sample(*row).is_err());
+        }
+        assert!(tables.nodes().row(0).unwrap().flags == 1 << 20);
+    }
+
     #[test]
     fn test_mutable_node_access() {
         let tables = TableCollection::new(1000.).unwrap();
@@ -735,13 +2106,104 @@ mod test {
             assert!(row.metadata.is_none());
         }
 
-        for row in tables.nodes_iter() {
-            assert!(close_enough(tables.nodes().time(row.id).unwrap(), row.time));
-            assert_eq!(tables.nodes().flags(row.id).unwrap(), row.flags);
-            assert_eq!(tables.nodes().population(row.id).unwrap(), row.population);
-            assert_eq!(tables.nodes().individual(row.id).unwrap(), row.individual);
-            assert!(row.metadata.is_none());
+        for row in tables.nodes_iter() {
+            assert!(close_enough(tables.nodes().time(row.id).unwrap(), row.time));
+            assert_eq!(tables.nodes().flags(row.id).unwrap(), row.flags);
+            assert_eq!(tables.nodes().population(row.id).unwrap(), row.population);
+            assert_eq!(tables.nodes().individual(row.id).unwrap(), row.individual);
+            assert!(row.metadata.is_none());
+        }
+    }
+
+    #[test]
+    fn test_node_row_optional_ids() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_population().unwrap();
+        tables.add_individual(0, &[], &[]).unwrap();
+        tables.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 0.0, 0, 0).unwrap();
+        let nodes = tables.nodes();
+        let row = nodes.row(0).unwrap();
+        assert!(row.individual_id().is_none());
+        assert!(row.population_id().is_none());
+        let row = nodes.row(1).unwrap();
+        assert_eq!(row.individual_id(), Some(0));
+        assert_eq!(row.population_id(), Some(0));
+    }
+
+    #[test]
+    fn test_table_ref_into_iterator() {
+        let mut tables = make_small_table_collection();
+        tables.add_individual(0, &[], &[]).unwrap();
+        tables.add_individual(0, &[], &[0]).unwrap();
+        let nodes = tables.nodes();
+        let mut num_rows = 0;
+        for row in &nodes {
+            assert!(row == nodes.row(row.id).unwrap());
+            num_rows += 1;
+        }
+        assert_eq!(num_rows, nodes.num_rows());
+
+        let edges = tables.edges();
+        assert_eq!((&edges).into_iter().count(), edges.num_rows() as usize);
+        let individuals = tables.individuals();
+        let ids = (&individuals).into_iter().map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![0, 1]);
+        assert!(individuals.iter().eq(individuals.into_iter()));
+    }
+
+    #[test]
+    fn test_table_iterator_len() {
+        let mut tables = make_small_table_collection();
+        tables.add_site(0.3, None).unwrap();
+        let nodes = tables.nodes();
+        assert_eq!(nodes.iter().len(), nodes.num_rows() as usize);
+        assert_eq!(tables.edges().iter().len(), 2);
+        assert_eq!(tables.sites().iter().len(), 1);
+        assert_eq!(tables.mutations().iter().len(), 0);
+        assert_eq!(tables.nodes_iter().len(), 3);
+
+        let mut iter = nodes.iter();
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        iter.next();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.by_ref().count(), 2);
+        assert!(iter.next().is_none());
+        assert_eq!(iter.len(), 0);
+    }
+
+    #[test]
+    fn test_table_iterator_rev() {
+        let mut tables = make_small_table_collection();
+        for (i, state) in [b"A", b"C", b"G", b"T"].iter().enumerate() {
+            tables
+                .add_mutation(0, 1, TSK_NULL, 1.0 - 0.1 * i as f64, Some(*state))
+                .unwrap();
         }
+        let mutations = tables.mutations();
+        let mut forward = mutations.iter().collect::<Vec<_>>();
+        forward.reverse();
+        let backward = mutations.iter().rev().collect::<Vec<_>>();
+        assert!(forward == backward);
+        assert_eq!(
+            tables
+                .mutations_iter()
+                .rev()
+                .map(|r| r.id)
+                .collect::<Vec<_>>(),
+            vec![3, 2, 1, 0]
+        );
+
+        // The front and back cursors meet in the middle.
+        let mut iter = mutations.iter();
+        assert_eq!(iter.next().unwrap().id, 0);
+        assert_eq!(iter.next_back().unwrap().id, 3);
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back().unwrap().id, 2);
+        assert_eq!(iter.next().unwrap().id, 1);
+        assert!(iter.next().is_none());
+        assert!(iter.next_back().is_none());
+        assert_eq!(iter.len(), 0);
     }
 
     #[test]
@@ -859,6 +2321,391 @@ mod test {
         assert_eq!(no_anc_state, 1);
     }
 
+    #[test]
+    fn test_node_time_and_flags_arrays() {
+        let tables = crate::test_fixtures::make_small_table_collection_two_trees();
+        let nodes = tables.nodes();
+        let time = nodes.time_array();
+        let flags = nodes.flags_array();
+        assert_eq!(time.len(), nodes.num_rows() as usize);
+        assert_eq!(flags.len(), nodes.num_rows() as usize);
+        for row in 0..nodes.num_rows() as tsk_id_t {
+            assert_eq!(time[row as usize], nodes.time(row).unwrap());
+            assert_eq!(flags[row as usize], nodes.flags(row).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_node_time_i64() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        for time in [
+            0.0,
+            10.0,
+            -3.0,
+            1e-10,
+            99.999_999_999,
+            2.5,
+            1e-3,
+            f64::NAN,
+            1e300,
+        ]
+        .iter()
+        {
+            tables.add_node(0, *time, TSK_NULL, TSK_NULL).unwrap();
+        }
+        let nodes = tables.nodes();
+        assert_eq!(nodes.time_i64(0).unwrap(), 0);
+        assert_eq!(nodes.time_i64(1).unwrap(), 10);
+        assert_eq!(nodes.time_i64(2).unwrap(), -3);
+        assert_eq!(nodes.time_i64(3).unwrap(), 0);
+        assert_eq!(nodes.time_i64(4).unwrap(), 100);
+        for row in 5..9 {
+            assert!(matches!(
+                nodes.time_i64(row),
+                Err(TskitError::ValueError {
+                    got: _,
+                    expected: _
+                })
+            ));
+        }
+        assert!(matches!(nodes.time_i64(9), Err(TskitError::IndexError)));
+    }
+
+    #[test]
+    fn test_node_population_and_individual_arrays() {
+        let mut tables = crate::test_fixtures::make_small_table_collection();
+        let pop = tables.add_population().unwrap();
+        let ind = tables.add_individual(0, &[], &[]).unwrap();
+        tables.add_node(0, 0.0, pop, ind).unwrap();
+        let nodes = tables.nodes();
+        let population = nodes.population_array();
+        let individual = nodes.individual_array();
+        assert_eq!(population, &[TSK_NULL, TSK_NULL, TSK_NULL, pop]);
+        assert_eq!(individual, &[TSK_NULL, TSK_NULL, TSK_NULL, ind]);
+        for row in 0..nodes.num_rows() as tsk_id_t {
+            assert_eq!(population[row as usize], nodes.population(row).unwrap());
+            assert_eq!(individual[row as usize], nodes.individual(row).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_site_position_array() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        assert!(tables.sites().position_array().is_empty());
+        for pos in [1., 10.5, 10.5, 999.].iter() {
+            tables.add_site(*pos, None).unwrap();
+        }
+        let sites = tables.sites();
+        let positions = sites.position_array();
+        assert_eq!(positions, &[1., 10.5, 10.5, 999.]);
+        for row in 0..sites.num_rows() as tsk_id_t {
+            assert_eq!(positions[row as usize], sites.position(row).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_site_at_position() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        assert_eq!(tables.sites().site_at_position(0.), None);
+        assert_eq!(tables.sites().nearest_site(0.), None);
+        for pos in [0., 10., 20., 20., 999.].iter() {
+            tables.add_site(*pos, None).unwrap();
+        }
+        let sites = tables.sites();
+
+        assert_eq!(sites.site_at_position(0.), Some(0));
+        assert_eq!(sites.site_at_position(10.), Some(1));
+        assert_eq!(sites.site_at_position(20.), Some(2));
+        assert_eq!(sites.site_at_position(999.), Some(4));
+        for pos in [-1., 5., 10.5, 998.999, 1000., f64::NAN].iter() {
+            assert_eq!(sites.site_at_position(*pos), None);
+        }
+
+        for (pos, site) in [
+            (-1., 0),
+            (0., 0),
+            (4.9, 0),
+            (5., 0),
+            (5.1, 1),
+            (15., 1),
+            (16., 2),
+            (20., 2),
+            (500., 2),
+            (998., 4),
+            (5000., 4),
+        ]
+        .iter()
+        {
+            assert_eq!(sites.nearest_site(*pos), Some(*site), "{}", pos);
+        }
+        assert_eq!(sites.nearest_site(f64::NAN), None);
+    }
+
+    #[test]
+    fn test_partial_eq() {
+        let a = make_small_table_collection();
+        let b = make_small_table_collection();
+        assert!(a == b);
+        let mut c = make_small_table_collection();
+        c.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        assert!(a != c);
+        assert!(c != a);
+        let mut d = TableCollection::new(999.).unwrap();
+        d.build_index().unwrap();
+        assert!(a != d);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut a = crate::test_fixtures::make_small_table_collection_two_trees();
+        a.add_site(100., Some(b"0")).unwrap();
+        a.add_mutation(0, 2, TSK_NULL, 0.5, Some(b"1")).unwrap();
+        a.add_mutation(0, 2, 0, 0.25, Some(b"0")).unwrap();
+        let b = a.deepcopy().unwrap();
+        let num_nodes = a.nodes().num_rows();
+        let num_edges = a.edges().num_rows();
+        a.extend(&b, num_nodes as tsk_id_t).unwrap();
+
+        assert_eq!(a.nodes().num_rows(), 2 * num_nodes);
+        assert_eq!(a.edges().num_rows(), 2 * num_edges);
+        assert_eq!(a.sites().num_rows(), 2);
+        assert_eq!(a.mutations().num_rows(), 4);
+        let offset = num_nodes as tsk_id_t;
+        for i in 0..num_edges as tsk_id_t {
+            let j = i + num_edges as tsk_id_t;
+            assert_eq!(
+                a.edges().parent(j).unwrap(),
+                a.edges().parent(i).unwrap() + offset
+            );
+            assert_eq!(
+                a.edges().child(j).unwrap(),
+                a.edges().child(i).unwrap() + offset
+            );
+            assert_eq!(a.edges().left(j).unwrap(), a.edges().left(i).unwrap());
+        }
+        for i in 0..num_nodes as tsk_id_t {
+            let j = i + offset;
+            assert_eq!(a.nodes().time(j).unwrap(), a.nodes().time(i).unwrap());
+        }
+        assert_eq!(a.mutations().site(2).unwrap(), 1);
+        assert_eq!(a.mutations().node(2).unwrap(), 2 + offset);
+        assert_eq!(a.mutations().parent(2).unwrap(), TSK_NULL);
+        assert_eq!(a.mutations().parent(3).unwrap(), 2);
+
+        let c = TableCollection::new(999.).unwrap();
+        assert!(matches!(
+            a.extend(&c, 0),
+            Err(TskitError::ValueError {
+                got: _,
+                expected: _
+            })
+        ));
+        assert!(a.extend(&b, -1).is_err());
+    }
+
+    #[test]
+    fn test_extend_node_id_offset() {
+        let mut a = crate::test_fixtures::make_small_table_collection_two_trees();
+        let b = a.deepcopy().unwrap();
+        let num_nodes = a.nodes().num_rows() as tsk_id_t;
+
+        // An offset past the current number of nodes is an error,
+        // and the tables are unchanged.
+        assert!(matches!(
+            a.extend(&b, num_nodes + 1),
+            Err(TskitError::ValueError {
+                got: _,
+                expected: _
+            })
+        ));
+        assert!(a == b);
+
+        // A smaller offset makes the new edges refer to existing nodes.
+        a.extend(&b, 1).unwrap();
+        assert_eq!(a.nodes().num_rows() as tsk_id_t, 2 * num_nodes);
+        let num_edges = b.edges().num_rows() as tsk_id_t;
+        for i in 0..num_edges {
+            assert_eq!(
+                a.edges().parent(i + num_edges).unwrap(),
+                b.edges().parent(i).unwrap() + 1
+            );
+        }
+    }
+
+    #[test]
+    fn test_keep_interval_drops_index() {
+        let mut tables = crate::test_fixtures::make_small_table_collection_two_trees();
+        assert!(tables.is_indexed());
+        // No edge is removed, but their left coordinates change.
+        tables.keep_interval(250., 1000.).unwrap();
+        assert_eq!(tables.edges().num_rows(), 6);
+        assert!(!tables.is_indexed());
+
+        let mut tables = crate::test_fixtures::make_small_table_collection_two_trees();
+        tables.add_mutation(5, 0, TSK_NULL, 0.0, None).unwrap();
+        assert!(matches!(
+            tables.keep_interval(250., 1000.),
+            Err(TskitError::IndexError)
+        ));
+    }
+
+    #[test]
+    fn test_add_rows_return_ids() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        let pop = tables.add_population().unwrap();
+        assert_eq!(pop, 0);
+        let parent = tables.add_node(0, 1.0, pop, TSK_NULL).unwrap();
+        let child = tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, pop, TSK_NULL)
+            .unwrap();
+        assert_eq!((parent, child), (0, 1));
+        let edge = tables.add_edge(0., 1000., parent, child).unwrap();
+        assert_eq!(edge, 0);
+        assert_eq!(tables.edges().parent(edge).unwrap(), parent);
+        assert_eq!(tables.add_edge(0., 1000., parent, child).unwrap(), 1);
+        let site = tables.add_site(1., None).unwrap();
+        let mutation = tables
+            .add_mutation(site, child, TSK_NULL, 0.5, None)
+            .unwrap();
+        assert_eq!(tables.mutations().site(mutation).unwrap(), site);
+        assert_eq!(tables.add_individual(0, &[], &[]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_edges_are_sorted() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        assert!(tables.edges_are_sorted());
+        tables.add_node(0, 2.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        // The parent of the first edge is older than that of the second.
+        tables.add_edge(0., 1000., 0, 1).unwrap();
+        tables.add_edge(0., 1000., 1, 2).unwrap();
+        tables.add_edge(0., 1000., 1, 3).unwrap();
+        assert!(!tables.edges_are_sorted());
+        assert!(tables
+            .check_integrity(TableIntegrityCheckOptions::CHECK_EDGE_ORDERING)
+            .is_err());
+
+        tables.full_sort(TableSortOptions::default()).unwrap();
+        assert!(tables.edges_are_sorted());
+        assert!(tables
+            .check_integrity(TableIntegrityCheckOptions::CHECK_EDGE_ORDERING)
+            .is_ok());
+
+        // Same parent, children out of order
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_edge(0., 1000., 0, 2).unwrap();
+        tables.add_edge(0., 1000., 0, 1).unwrap();
+        assert!(!tables.edges_are_sorted());
+
+        // Invalid parent
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_edge(0., 1000., 0, 1).unwrap();
+        assert!(!tables.edges_are_sorted());
+    }
+
+    #[test]
+    fn test_ancestral_states_raw() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        let (states, offsets) = tables.sites().ancestral_states_raw();
+        assert!(states.is_empty());
+        assert_eq!(offsets, &[0]);
+
+        tables.add_site(0.3, Some(b"Eggnog")).unwrap();
+        tables.add_site(0.5, None).unwrap();
+        tables.add_site(0.9, Some(b"Hot Toddy")).unwrap();
+        let sites = tables.sites();
+        let (states, offsets) = sites.ancestral_states_raw();
+        assert_eq!(offsets.len(), sites.num_rows() as usize + 1);
+        for i in 0..sites.num_rows() as usize {
+            let state = &states[offsets[i] as usize..offsets[i + 1] as usize];
+            match sites.ancestral_state(i as tsk_id_t).unwrap() {
+                Some(s) => assert_eq!(state, s.as_slice()),
+                None => assert!(state.is_empty()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_derived_states_raw() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables
+            .add_mutation(0, 0, crate::TSK_NULL, 1.0, None)
+            .unwrap();
+        tables
+            .add_mutation(0, 1, crate::TSK_NULL, 1.0, Some(b"pajamas"))
+            .unwrap();
+        tables
+            .add_mutation(1, 2, crate::TSK_NULL, 2.0, None)
+            .unwrap();
+        tables
+            .add_mutation(2, 3, crate::TSK_NULL, 3.0, Some(b"more pajamas"))
+            .unwrap();
+        let mutations = tables.mutations();
+        let (states, offsets) = mutations.derived_states_raw();
+        assert_eq!(offsets.len(), mutations.num_rows() as usize + 1);
+        assert_eq!(states.len(), "pajamasmore pajamas".len());
+        for i in 0..mutations.num_rows() as usize {
+            let state = &states[offsets[i] as usize..offsets[i + 1] as usize];
+            match mutations.derived_state(i as tsk_id_t).unwrap() {
+                Some(s) => assert_eq!(state, s.as_slice()),
+                None => assert!(state.is_empty()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_state_str() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_site(0.3, Some(b"A")).unwrap();
+        tables.add_site(0.5, None).unwrap();
+        tables.add_site(0.9, Some(&[0xff, 0xfe])).unwrap();
+        tables
+            .add_mutation(0, 0, crate::TSK_NULL, 1.0, Some(b"AT"))
+            .unwrap();
+        tables
+            .add_mutation(1, 0, crate::TSK_NULL, 1.0, None)
+            .unwrap();
+        tables
+            .add_mutation(2, 0, crate::TSK_NULL, 1.0, Some(&[0xc3, 0x28]))
+            .unwrap();
+
+        let sites = tables.sites();
+        assert_eq!(sites.ancestral_state_str(0).unwrap(), Some("A"));
+        assert_eq!(sites.ancestral_state_str(1).unwrap(), None);
+        assert!(matches!(
+            sites.ancestral_state_str(2),
+            Err(TskitError::ValueError {
+                got: _,
+                expected: _
+            })
+        ));
+        assert!(matches!(
+            sites.ancestral_state_str(3),
+            Err(TskitError::IndexError)
+        ));
+
+        let mutations = tables.mutations();
+        assert_eq!(mutations.derived_state_str(0).unwrap(), Some("AT"));
+        assert_eq!(mutations.derived_state_str(1).unwrap(), None);
+        assert!(matches!(
+            mutations.derived_state_str(2),
+            Err(TskitError::ValueError {
+                got: _,
+                expected: _
+            })
+        ));
+        assert!(matches!(
+            mutations.derived_state_str(-1),
+            Err(TskitError::IndexError)
+        ));
+    }
+
     fn close_enough(a: f64, b: f64) -> bool {
         (a - b).abs() < f64::EPSILON
     }
@@ -940,6 +2787,28 @@ mod test {
         assert_eq!(nmuts, tables.mutations().num_rows() - 1);
     }
 
+    #[test]
+    fn test_add_mutation_with_optional_time() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables
+            .add_mutation_with_optional_time(0, 0, TSK_NULL, None, None)
+            .unwrap();
+        tables
+            .add_mutation_with_optional_time(0, 0, TSK_NULL, Some(1.123), None)
+            .unwrap();
+        tables.add_mutation(0, 0, TSK_NULL, f64::NAN, None).unwrap();
+        let mutations = tables.mutations();
+        assert!(mutations.time_is_unknown(0).unwrap());
+        assert!(!mutations.time_is_unknown(1).unwrap());
+        assert!(close_enough(mutations.time(1).unwrap(), 1.123));
+        // Only the specific NaN value means "unknown".
+        assert!(!mutations.time_is_unknown(2).unwrap());
+        assert!(matches!(
+            mutations.time_is_unknown(3),
+            Err(TskitError::IndexError)
+        ));
+    }
+
     struct F {
         x: i32,
         y: u32,
@@ -1035,7 +2904,6 @@ mod test {
 
     #[test]
     fn test_dump_tables() {
-        let treefile = "trees.trees";
         let mut tables = TableCollection::new(1000.).unwrap();
         tables.add_population().unwrap();
         tables
@@ -1055,14 +2923,66 @@ mod test {
             )
             .unwrap();
         tables.add_edge(0., tables.sequence_length(), 1, 0).unwrap();
+        let bytes = tables.dump_bytes(TableOutputOptions::default()).unwrap();
+
+        let tables2 = TableCollection::load_bytes(&bytes).unwrap();
+        assert!(tables.equals(&tables2, TableEqualityOptions::default()));
+    }
+
+    #[test]
+    fn test_load_bytes_invalid() {
+        assert!(matches!(
+            TableCollection::load_bytes(&[]),
+            Err(TskitError::ErrorCode { code: _ })
+        ));
+        let tables = make_small_table_collection();
+        let bytes = tables.dump_bytes(TableOutputOptions::default()).unwrap();
+        assert!(TableCollection::load_bytes(&bytes[..bytes.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn test_metadata_schema_roundtrip() {
+        let schema = r#"{"codec":"json"}"#;
+        let mut tables = TableCollection::new(1000.).unwrap();
+        assert!(tables.nodes().metadata_schema().is_none());
+        tables.set_node_metadata_schema(schema).unwrap();
+        tables.set_mutation_metadata_schema(schema).unwrap();
+        assert_eq!(tables.nodes().metadata_schema().unwrap(), schema);
+        let bytes = tables.dump_bytes(TableOutputOptions::default()).unwrap();
+
+        let tables2 = TableCollection::load_bytes(&bytes).unwrap();
+        assert_eq!(tables2.nodes().metadata_schema().unwrap(), schema);
+        assert_eq!(tables2.mutations().metadata_schema().unwrap(), schema);
+        assert!(tables2.edges().metadata_schema().is_none());
+        assert!(tables.equals(&tables2, TableEqualityOptions::default()));
+    }
+
+    #[test]
+    fn test_peek_metadata_schemas() {
+        // peek_metadata_schemas needs a real file, so use a
+        // unique path in the temporary directory.
+        let path = std::env::temp_dir().join(format!(
+            "tskit_peek_metadata_schemas_{}.trees",
+            std::process::id()
+        ));
+        let treefile = path.to_str().unwrap();
+        let schema = r#"{"codec":"json"}"#;
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.set_metadata_schema(schema).unwrap();
+        tables.set_site_metadata_schema(schema).unwrap();
+        tables.build_index().unwrap();
         tables
-            .dump(&treefile, TableOutputOptions::default())
+            .dump(treefile, TableOutputOptions::default())
             .unwrap();
 
-        let tables2 = TableCollection::new_from_file(&treefile).unwrap();
-        assert!(tables.equals(&tables2, TableEqualityOptions::default()));
+        let summary = TableCollection::peek_metadata_schemas(treefile).unwrap();
+        std::fs::remove_file(treefile).unwrap();
+        assert_eq!(summary.top_level.as_deref(), Some(schema));
+        assert_eq!(summary.sites.as_deref(), Some(schema));
+        assert!(summary.nodes.is_none());
+        assert!(summary.populations.is_none());
 
-        std::fs::remove_file(&treefile).unwrap();
+        assert!(TableCollection::peek_metadata_schemas("no_such_file.trees").is_err());
     }
 
     #[test]
@@ -1144,6 +3064,87 @@ mod test {
 
         assert!(tables.individuals().row(0).unwrap() == tables.individuals().row(0).unwrap());
     }
+
+    #[test]
+    fn test_node_builder() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        let mut builder_tables = TableCollection::new(10.).unwrap();
+
+        tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, 1, 2)
+            .unwrap();
+        tables.add_node(0, 1.5, TSK_NULL, 3).unwrap();
+        tables.add_node(0, 2.0, TSK_NULL, TSK_NULL).unwrap();
+
+        builder_tables
+            .add_node_builder()
+            .individual(2)
+            .population(1)
+            .sample(true)
+            .build()
+            .unwrap();
+        builder_tables
+            .add_node_builder()
+            .time(1.5)
+            .individual(3)
+            .build()
+            .unwrap();
+        let id = builder_tables
+            .add_node_builder()
+            .flags(crate::TSK_NODE_IS_SAMPLE)
+            .sample(false)
+            .time(2.0)
+            .build()
+            .unwrap();
+        assert_eq!(id, 2);
+
+        assert_eq!(tables.nodes().num_rows(), builder_tables.nodes().num_rows());
+        for (a, b) in tables.nodes_iter().zip(builder_tables.nodes_iter()) {
+            assert!(a == b);
+        }
+    }
+
+    #[test]
+    fn test_semantically_equals() {
+        let tables = make_small_table_collection();
+        let mut reordered = TableCollection::new(1000.).unwrap();
+        reordered.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        reordered.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        reordered.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        reordered.add_edge(0., 1000., 0, 2).unwrap();
+        reordered.add_edge(0., 1000., 0, 1).unwrap();
+
+        assert!(!tables.equals(&reordered, TableEqualityOptions::default()));
+        assert!(tables.semantically_equals(&reordered));
+        assert!(reordered.semantically_equals(&tables));
+
+        // The inputs are not modified
+        assert_eq!(reordered.edges().child(0).unwrap(), 2);
+        assert!(tables.is_indexed());
+
+        reordered.add_node(0, 2.0, TSK_NULL, TSK_NULL).unwrap();
+        reordered.add_edge(0., 1000., 3, 0).unwrap();
+        assert!(!tables.semantically_equals(&reordered));
+    }
+
+    #[test]
+    fn test_check_integrity() {
+        let tables = make_small_table_collection();
+        tables
+            .check_integrity(TableIntegrityCheckOptions::CHECK_TREES)
+            .unwrap();
+
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables.add_site(5., None).unwrap();
+        tables.add_site(1., None).unwrap();
+        tables
+            .check_integrity(TableIntegrityCheckOptions::default())
+            .unwrap();
+        match tables.check_integrity(TableIntegrityCheckOptions::CHECK_SITE_ORDERING) {
+            Err(e) => assert!(matches!(e, TskitError::ErrorCode { code: _ })),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1151,6 +3152,19 @@ mod test_bad_metadata {
     use super::*;
     use crate::test_fixtures::bad_metadata::*;
 
+    #[test]
+    fn test_equals_ignore_metadata() {
+        let mut a = TableCollection::new(1000.).unwrap();
+        let mut b = TableCollection::new(1000.).unwrap();
+        a.add_node_with_metadata(0, 1.0, TSK_NULL, TSK_NULL, Some(&F { x: 1, y: 2 }))
+            .unwrap();
+        b.add_node_with_metadata(0, 1.0, TSK_NULL, TSK_NULL, Some(&F { x: -1, y: 3 }))
+            .unwrap();
+        assert!(!a.equals(&b, TableEqualityOptions::default()));
+        assert!(a.equals(&b, TableEqualityOptions::IGNORE_METADATA));
+        assert!(!a.equals(&b, TableEqualityOptions::IGNORE_TS_METADATA));
+    }
+
     #[test]
     fn test_bad_mutation_metadata_roundtrip() {
         let mut tables = TableCollection::new(1.).unwrap();
@@ -1162,4 +3176,152 @@ mod test_bad_metadata {
             panic!("expected an error!!");
         }
     }
+
+    #[test]
+    fn test_top_level_metadata_roundtrip() {
+        let schema = r#"{"codec":"struct"}"#;
+        let mut tables = TableCollection::new(1.).unwrap();
+        assert!(tables.metadata::<F>().unwrap().is_none());
+        assert!(tables.metadata_schema().is_none());
+        tables.set_metadata(&F { x: -1, y: 2 }).unwrap();
+        tables.set_metadata_schema(schema).unwrap();
+
+        let copy = tables.deepcopy().unwrap();
+        let bytes = tables
+            .dump_bytes(crate::TableOutputOptions::default())
+            .unwrap();
+        let loaded = TableCollection::load_bytes(&bytes).unwrap();
+
+        for t in [&tables, &copy, &loaded].iter() {
+            let md = t.metadata::<F>().unwrap().unwrap();
+            assert_eq!(md.x, -1);
+            assert_eq!(md.y, 2);
+            assert_eq!(t.metadata_schema().unwrap(), schema);
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_node_metadata_json() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct NodeMetadata {
+            name: String,
+            value: i32,
+        }
+
+        impl MetadataRoundtrip for NodeMetadata {
+            fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+                handle_metadata_return!(serde_json::to_vec(&self))
+            }
+            fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+                handle_metadata_return!(serde_json::from_slice(md))
+            }
+        }
+
+        let mut tables = TableCollection::new(1.).unwrap();
+        let md = NodeMetadata {
+            name: String::from("x"),
+            value: -3,
+        };
+        tables
+            .add_node_with_metadata(0, 0.0, TSK_NULL, TSK_NULL, Some(&md))
+            .unwrap();
+        tables.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        assert!(matches!(
+            tables.nodes().metadata_json(0),
+            Err(TskitError::ValueError { .. })
+        ));
+        tables
+            .set_node_metadata_schema(r#"{"codec":"struct"}"#)
+            .unwrap();
+        assert!(tables.nodes().metadata_json(0).is_err());
+        tables
+            .set_node_metadata_schema(r#"{"codec":"json"}"#)
+            .unwrap();
+        let json = tables.nodes().metadata_json(0).unwrap().unwrap();
+        assert_eq!(json, r#"{"name":"x","value":-3}"#);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["value"], -3);
+        assert!(tables.nodes().metadata_json(1).unwrap().is_none());
+        assert!(matches!(
+            tables.nodes().metadata_json(2),
+            Err(TskitError::IndexError)
+        ));
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct PopulationMetadata {
+        name: String,
+    }
+
+    impl crate::metadata::MetadataRoundtrip for PopulationMetadata {
+        fn encode(&self) -> Result<Vec<u8>, crate::metadata::MetadataError> {
+            handle_metadata_return!(bincode::serialize(&self))
+        }
+
+        fn decode(md: &[u8]) -> Result<Self, crate::metadata::MetadataError> {
+            handle_metadata_return!(bincode::deserialize(md))
+        }
+    }
+
+    #[test]
+    fn test_find_population() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_population().unwrap();
+        for name in ["ancestral", "derived"].iter() {
+            let md = PopulationMetadata {
+                name: name.to_string(),
+            };
+            tables.add_population_with_metadata(Some(&md)).unwrap();
+        }
+        let populations = tables.populations();
+        assert_eq!(
+            populations
+                .find(|md: &PopulationMetadata| md.name == "ancestral")
+                .unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            populations
+                .find(|md: &PopulationMetadata| md.name == "derived")
+                .unwrap(),
+            Some(2)
+        );
+        assert!(populations
+            .find(|md: &PopulationMetadata| md.name == "missing")
+            .unwrap()
+            .is_none());
+
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables
+            .add_population_with_metadata(Some(&F { x: 1, y: 2 }))
+            .unwrap();
+        assert!(tables.populations().find(|md: &Ff| md.x == 1).is_err());
+    }
+
+    #[test]
+    fn test_decode_all_mutation_metadata() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables
+            .add_mutation_with_metadata(0, 0, crate::TSK_NULL, 0.0, None, Some(&F { x: 1, y: 11 }))
+            .unwrap();
+        tables
+            .add_mutation(0, 0, crate::TSK_NULL, 0.0, None)
+            .unwrap();
+        tables
+            .add_mutation_with_metadata(0, 0, crate::TSK_NULL, 0.0, None, Some(&F { x: -2, y: 3 }))
+            .unwrap();
+        let md = tables.mutations().decode_all_metadata::<F>().unwrap();
+        assert_eq!(md.len(), 3);
+        match &md[0] {
+            Some(f) => assert_eq!((f.x, f.y), (1, 11)),
+            None => panic!("expected metadata"),
+        }
+        assert!(md[1].is_none());
+        match &md[2] {
+            Some(f) => assert_eq!((f.x, f.y), (-2, 3)),
+            None => panic!("expected metadata"),
+        }
+        assert!(tables.mutations().decode_all_metadata::<Ff>().is_err());
+    }
 }
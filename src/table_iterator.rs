@@ -1,10 +1,48 @@
-use crate::tsk_id_t;
+use crate::{tsk_id_t, tsk_size_t};
 
 pub struct TableIterator<T> {
     pub(crate) table: T,
     pub(crate) pos: tsk_id_t,
+    // One past the last row to be returned.
+    pub(crate) end: tsk_id_t,
 }
 
-pub(crate) fn make_table_iterator<TABLE>(table: TABLE) -> TableIterator<TABLE> {
-    TableIterator { table, pos: 0 }
+pub(crate) fn make_table_iterator<TABLE>(
+    table: TABLE,
+    num_rows: tsk_size_t,
+) -> TableIterator<TABLE> {
+    TableIterator {
+        table,
+        pos: 0,
+        end: num_rows as tsk_id_t,
+    }
+}
+
+impl<T> TableIterator<T> {
+    // Return the next row id from the front,
+    // or None if the front and back have met.
+    pub(crate) fn next_row(&mut self) -> Option<tsk_id_t> {
+        if self.pos < self.end {
+            self.pos += 1;
+            Some(self.pos - 1)
+        } else {
+            None
+        }
+    }
+
+    // Return the next row id from the back,
+    // or None if the front and back have met.
+    pub(crate) fn next_row_back(&mut self) -> Option<tsk_id_t> {
+        if self.pos < self.end {
+            self.end -= 1;
+            Some(self.end)
+        } else {
+            None
+        }
+    }
+
+    // The number of rows not yet returned.
+    pub(crate) fn remaining(&self) -> usize {
+        (self.end - self.pos) as usize
+    }
 }
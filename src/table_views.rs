@@ -0,0 +1,105 @@
+use crate::bindings as ll_bindings;
+use crate::EdgeTable;
+use crate::IndividualTable;
+use crate::MigrationTable;
+use crate::MutationTable;
+use crate::NodeTable;
+use crate::PopulationTable;
+use crate::SiteTable;
+
+/// Immutable views of all tables of a [`crate::TreeSequence`].
+///
+/// The views are created once, avoiding the cost of
+/// creating new table objects via [`crate::TableAccess`]
+/// for each access.
+///
+/// These are not created directly.
+/// Instead, use [`crate::TreeSequence::tables`].
+///
+/// # Examples
+///
+/// ```
+/// let tables = tskit::TableCollection::new(1.).unwrap();
+/// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+/// let views = treeseq.tables();
+/// assert_eq!(views.nodes().num_rows(), 0);
+/// assert_eq!(views.edges().num_rows(), 0);
+/// ```
+pub struct TableViews<'a> {
+    edges: EdgeTable<'a>,
+    individuals: IndividualTable<'a>,
+    migrations: MigrationTable<'a>,
+    nodes: NodeTable<'a>,
+    sites: SiteTable<'a>,
+    mutations: MutationTable<'a>,
+    populations: PopulationTable<'a>,
+}
+
+impl<'a> TableViews<'a> {
+    pub(crate) fn new_from_tables(tables: &'a ll_bindings::tsk_table_collection_t) -> Self {
+        Self {
+            edges: EdgeTable::new_from_table(&tables.edges),
+            individuals: IndividualTable::new_from_table(&tables.individuals),
+            migrations: MigrationTable::new_from_table(&tables.migrations),
+            nodes: NodeTable::new_from_table(&tables.nodes),
+            sites: SiteTable::new_from_table(&tables.sites),
+            mutations: MutationTable::new_from_table(&tables.mutations),
+            populations: PopulationTable::new_from_table(&tables.populations),
+        }
+    }
+
+    /// Get reference to the [``EdgeTable``](crate::EdgeTable).
+    pub fn edges(&self) -> &EdgeTable<'a> {
+        &self.edges
+    }
+
+    /// Get reference to the [``IndividualTable``](crate::IndividualTable).
+    pub fn individuals(&self) -> &IndividualTable<'a> {
+        &self.individuals
+    }
+
+    /// Get reference to the [``MigrationTable``](crate::MigrationTable).
+    pub fn migrations(&self) -> &MigrationTable<'a> {
+        &self.migrations
+    }
+
+    /// Get reference to the [``NodeTable``](crate::NodeTable).
+    pub fn nodes(&self) -> &NodeTable<'a> {
+        &self.nodes
+    }
+
+    /// Get reference to the [``SiteTable``](crate::SiteTable).
+    pub fn sites(&self) -> &SiteTable<'a> {
+        &self.sites
+    }
+
+    /// Get reference to the [``MutationTable``](crate::MutationTable).
+    pub fn mutations(&self) -> &MutationTable<'a> {
+        &self.mutations
+    }
+
+    /// Get reference to the [``PopulationTable``](crate::PopulationTable).
+    pub fn populations(&self) -> &PopulationTable<'a> {
+        &self.populations
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_fixtures::treeseq_from_small_table_collection_two_trees;
+    use crate::TableAccess;
+
+    #[test]
+    fn test_views_match_table_access() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let views = treeseq.tables();
+        assert_eq!(views.nodes().num_rows(), treeseq.nodes().num_rows());
+        assert_eq!(views.edges().num_rows(), treeseq.edges().num_rows());
+        for row in 0..views.nodes().num_rows() as crate::tsk_id_t {
+            assert_eq!(
+                views.nodes().time(row).unwrap(),
+                treeseq.nodes().time(row).unwrap()
+            );
+        }
+    }
+}
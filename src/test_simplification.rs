@@ -31,13 +31,96 @@ mod tests {
     fn test_simplify_treeseq() {
         let ts = treeseq_from_small_table_collection_two_trees();
         let samples = ts.sample_nodes();
-        let (_, idmap_option) = ts
-            .simplify(&samples, SimplificationOptions::default(), true)
+        let (_, idmap) = ts
+            .simplify_keep_idmap(&samples, SimplificationOptions::default())
             .unwrap();
-        assert!(idmap_option.is_some());
-        let idmap = idmap_option.unwrap();
+        assert_eq!(idmap.len(), ts.nodes().num_rows() as usize);
         for &i in samples {
             assert_ne!(idmap[i as usize], TSK_NULL);
         }
     }
+
+    #[test]
+    fn test_simplify_treeseq_without_idmap() {
+        let ts = treeseq_from_small_table_collection_two_trees();
+        let samples = ts.sample_nodes();
+        let simplified = ts
+            .simplify(samples, SimplificationOptions::default())
+            .unwrap();
+        assert_eq!(simplified.num_samples(), ts.num_samples());
+        assert_eq!(simplified.num_trees(), ts.num_trees());
+    }
+
+    #[test]
+    fn test_simplify_invalid_samples() {
+        let ts = treeseq_from_small_table_collection_two_trees();
+        for samples in [vec![2, 100], vec![TSK_NULL, 2], vec![-2]].iter() {
+            assert!(matches!(
+                ts.simplify(samples, SimplificationOptions::default()),
+                Err(crate::TskitError::ValueError { .. })
+            ));
+            let mut tables = make_small_table_collection_two_trees();
+            assert!(matches!(
+                tables.simplify(samples, SimplificationOptions::default(), false),
+                Err(crate::TskitError::ValueError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_simplify_filter_all() {
+        let make_tables = || {
+            let mut tables = make_small_table_collection_two_trees();
+            tables.add_population().unwrap();
+            tables.add_site(100., Some(b"0")).unwrap();
+            tables.add_site(200., Some(b"0")).unwrap();
+            tables
+                .add_mutation(0, 2, TSK_NULL, 0.5, Some(b"1"))
+                .unwrap();
+            tables
+        };
+        let samples = [2, 3, 4, 5];
+
+        let mut tables = make_tables();
+        tables
+            .simplify(&samples, SimplificationOptions::default(), false)
+            .unwrap();
+        assert_eq!(tables.sites().num_rows(), 2);
+        assert_eq!(tables.populations().num_rows(), 1);
+
+        let mut tables = make_tables();
+        tables
+            .simplify(&samples, SimplificationOptions::filter_all(), false)
+            .unwrap();
+        assert_eq!(tables.sites().num_rows(), 1);
+        assert_eq!(tables.sites().position(0).unwrap(), 100.);
+        assert_eq!(tables.populations().num_rows(), 0);
+        assert_eq!(tables.mutations().site(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_translate_samples() {
+        let mut tables = make_small_table_collection_two_trees();
+        let idmap = tables
+            .simplify(&[2, 3], SimplificationOptions::default(), true)
+            .unwrap()
+            .unwrap();
+        let new = crate::TableCollection::translate_samples(&idmap, &[3, 2]).unwrap();
+        assert_eq!(new, vec![1, 0]);
+        assert!(crate::TableCollection::translate_samples(&idmap, &[])
+            .unwrap()
+            .is_empty());
+
+        // Node 4 is not in the ancestry of the samples.
+        assert!(matches!(
+            crate::TableCollection::translate_samples(&idmap, &[2, 4]),
+            Err(crate::TskitError::ValueError { .. })
+        ));
+        for bad in [TSK_NULL, idmap.len() as tsk_id_t].iter() {
+            assert!(matches!(
+                crate::TableCollection::translate_samples(&idmap, &[*bad]),
+                Err(crate::TskitError::IndexError)
+            ));
+        }
+    }
 }
@@ -63,7 +63,7 @@ pub trait TableAccess {
     /// Return an iterator over the edges.
     /// See [`EdgeTable::iter`] for details.
     fn edges_iter(&self) -> EdgeTableIterator {
-        make_table_iterator::<EdgeTable>(self.edges())
+        make_table_iterator::<EdgeTable>(self.edges(), self.edges().num_rows())
     }
 
     /// Get reference to the [``NodeTable``](crate::NodeTable).
@@ -72,7 +72,7 @@ pub trait TableAccess {
     /// Return an iterator over the nodes.
     /// See [`NodeTable::iter`] for details.
     fn nodes_iter(&self) -> NodeTableIterator {
-        make_table_iterator::<NodeTable>(self.nodes())
+        make_table_iterator::<NodeTable>(self.nodes(), self.nodes().num_rows())
     }
 
     /// Get reference to the [``MutationTable``](crate::MutationTable).
@@ -81,7 +81,7 @@ pub trait TableAccess {
     /// Return an iterator over the mutations.
     /// See [`MutationTable::iter`] for details.
     fn mutations_iter(&self) -> MutationTableIterator {
-        make_table_iterator::<MutationTable>(self.mutations())
+        make_table_iterator::<MutationTable>(self.mutations(), self.mutations().num_rows())
     }
 
     /// Get reference to the [``SiteTable``](crate::SiteTable).
@@ -90,7 +90,7 @@ pub trait TableAccess {
     /// Return an iterator over the sites.
     /// See [`SiteTable::iter`] for details.
     fn sites_iter(&self) -> SiteTableIterator {
-        make_table_iterator::<SiteTable>(self.sites())
+        make_table_iterator::<SiteTable>(self.sites(), self.sites().num_rows())
     }
 
     /// Get reference to the [``PopulationTable``](crate::PopulationTable).
@@ -99,7 +99,7 @@ pub trait TableAccess {
     /// Return an iterator over the populations.
     /// See [`PopulationTable::iter`] for details.
     fn populations_iter(&self) -> PopulationTableIterator {
-        make_table_iterator::<PopulationTable>(self.populations())
+        make_table_iterator::<PopulationTable>(self.populations(), self.populations().num_rows())
     }
 
     /// Get reference to the [``MigrationTable``](crate::MigrationTable).
@@ -108,7 +108,7 @@ pub trait TableAccess {
     /// Return an iterator over the migration events.
     /// See [`MigrationTable::iter`] for details.
     fn migrations_iter(&self) -> MigrationTableIterator {
-        make_table_iterator::<MigrationTable>(self.migrations())
+        make_table_iterator::<MigrationTable>(self.migrations(), self.migrations().num_rows())
     }
 
     /// Get reference to the [``IndividualTable``](crate::IndividualTable).
@@ -117,7 +117,7 @@ pub trait TableAccess {
     /// Return an iterator over the individuals.
     /// See [`IndividualTable::iter`] for details.
     fn individuals_iter(&self) -> IndividualTableIterator {
-        make_table_iterator::<IndividualTable>(self.individuals())
+        make_table_iterator::<IndividualTable>(self.individuals(), self.individuals().num_rows())
     }
 }
 
@@ -196,4 +196,30 @@ pub trait NodeListGenerator: TableAccess {
     fn create_node_id_vector(&self, f: impl FnMut(&crate::NodeTableRow) -> bool) -> Vec<tsk_id_t> {
         self.nodes().create_node_id_vector(f)
     }
+
+    /// Obtain a vector containing the indexes ("ids") of all
+    /// sample nodes whose time is within `tol` of `time`.
+    ///
+    /// This is useful for working with ancient samples.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TSK_NULL;
+    /// use tskit::NodeListGenerator;
+    ///
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables
+    ///     .add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, TSK_NULL, TSK_NULL)
+    ///     .unwrap();
+    /// tables
+    ///     .add_node(tskit::TSK_NODE_IS_SAMPLE, 10.0, TSK_NULL, TSK_NULL)
+    ///     .unwrap();
+    /// assert_eq!(tables.samples_at_time(10.0, 1e-6), vec![1]);
+    /// ```
+    fn samples_at_time(&self, time: f64, tol: f64) -> Vec<tsk_id_t> {
+        self.create_node_id_vector(|row: &crate::NodeTableRow| {
+            row.is_sample() && (row.time - time).abs() <= tol
+        })
+    }
 }
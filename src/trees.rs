@@ -51,6 +51,12 @@ impl Tree {
     }
 
     fn new(ts: &TreeSequence, flags: TreeFlags) -> Result<Self, TskitError> {
+        if flags.contains(TreeFlags::SAMPLE_LISTS | TreeFlags::NO_SAMPLE_COUNTS) {
+            return Err(TskitError::ValueError {
+                got: String::from("SAMPLE_LISTS | NO_SAMPLE_COUNTS"),
+                expected: String::from("at most one of SAMPLE_LISTS and NO_SAMPLE_COUNTS"),
+            });
+        }
         let mut tree = Self::wrap(unsafe { (*(*ts.inner).tables).nodes.num_rows }, flags);
         let mut rv =
             unsafe { ll_bindings::tsk_tree_init(tree.as_mut_ptr(), ts.as_ptr(), flags.bits()) };
@@ -332,6 +338,41 @@ impl Tree {
         )
     }
 
+    /// Return the parent of each node as a [`crate::NodeArray`].
+    ///
+    /// See [`Tree::parent_array`] for the raw slice.
+    pub fn parent_node_array(&self) -> crate::NodeArray<'_> {
+        crate::NodeArray::from(self.parent_array())
+    }
+
+    /// Return the left child of each node as a [`crate::NodeArray`].
+    ///
+    /// See [`Tree::left_child_array`] for the raw slice.
+    pub fn left_child_node_array(&self) -> crate::NodeArray<'_> {
+        crate::NodeArray::from(self.left_child_array())
+    }
+
+    /// Return the right child of each node as a [`crate::NodeArray`].
+    ///
+    /// See [`Tree::right_child_array`] for the raw slice.
+    pub fn right_child_node_array(&self) -> crate::NodeArray<'_> {
+        crate::NodeArray::from(self.right_child_array())
+    }
+
+    /// Return the left sib of each node as a [`crate::NodeArray`].
+    ///
+    /// See [`Tree::left_sib_array`] for the raw slice.
+    pub fn left_sib_node_array(&self) -> crate::NodeArray<'_> {
+        crate::NodeArray::from(self.left_sib_array())
+    }
+
+    /// Return the right sib of each node as a [`crate::NodeArray`].
+    ///
+    /// See [`Tree::right_sib_array`] for the raw slice.
+    pub fn right_sib_node_array(&self) -> crate::NodeArray<'_> {
+        crate::NodeArray::from(self.right_sib_array())
+    }
+
     /// Return the `[left, right)` coordinates of the tree.
     pub fn interval(&self) -> (f64, f64) {
         unsafe { ((*self.as_ptr()).left, (*self.as_ptr()).right) }
@@ -344,15 +385,253 @@ impl Tree {
         i.1 - i.0
     }
 
+    /// Move to the tree whose interval contains `position`.
+    ///
+    /// The tree is moved by stepping forwards or backwards from
+    /// the current tree.  If restarting from the first (or last)
+    /// tree covers less of the genome than stepping from the
+    /// current tree, then the seek restarts from that end instead.
+    ///
+    /// # Note
+    ///
+    /// The cost of a seek is proportional to the number of trees
+    /// between the current tree and the target.  Thus, a series of
+    /// seeks to increasing (or decreasing) positions has the same
+    /// total cost as one pass over the trees.
+    ///
+    /// After a seek, the streaming iterator methods continue from
+    /// the new tree.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `position` is not within
+    ///   `[0, sequence_length)`.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let mut tree = treeseq.tree_iterator(tskit::TreeFlags::default()).unwrap();
+    /// tree.seek(500.).unwrap();
+    /// assert_eq!(tree.interval(), (0., 1000.));
+    /// ```
+    pub fn seek(&mut self, position: f64) -> Result<(), TskitError> {
+        self.seek_counting_steps(position).map(|_| ())
+    }
+
+    // Returns the number of calls to tsk_tree_{first,last,next,prev}
+    // needed to reach the target.
+    fn seek_counting_steps(&mut self, position: f64) -> Result<usize, TskitError> {
+        let sequence_length =
+            unsafe { ll_bindings::tsk_treeseq_get_sequence_length((*self.as_ptr()).tree_sequence) };
+        if !(0.0..sequence_length).contains(&position) {
+            return Err(TskitError::ValueError {
+                got: format!("{}", position),
+                expected: format!("position in [0, {})", sequence_length),
+            });
+        }
+
+        let index = unsafe { ll_bindings::tsk_tree_get_index(self.as_ptr()) };
+        let (left, right) = self.interval();
+        let forward = if index == TSK_NULL {
+            position < sequence_length - position
+        } else if position >= right {
+            // Stepping forwards from here vs backwards from the last tree.
+            position - right <= sequence_length - position
+        } else if position < left {
+            // Stepping backwards from here vs forwards from the first tree.
+            left - position > position
+        } else {
+            return Ok(0);
+        };
+
+        let mut steps = 0;
+        if forward && (index == TSK_NULL || position < left) {
+            self.step(ll_bindings::tsk_tree_first)?;
+            steps += 1;
+        } else if !forward && (index == TSK_NULL || position >= right) {
+            self.step(ll_bindings::tsk_tree_last)?;
+            steps += 1;
+        }
+
+        loop {
+            let (left, right) = self.interval();
+            if position >= left && position < right {
+                break;
+            }
+            match forward {
+                true => self.step(ll_bindings::tsk_tree_next)?,
+                false => self.step(ll_bindings::tsk_tree_prev)?,
+            }
+            steps += 1;
+        }
+
+        self.advanced = true;
+        self.current_tree = unsafe { ll_bindings::tsk_tree_get_index(self.as_ptr()) } + 1;
+        Ok(steps)
+    }
+
+    fn step(
+        &mut self,
+        f: unsafe extern "C" fn(*mut ll_bindings::tsk_tree_t) -> i32,
+    ) -> Result<(), TskitError> {
+        let rv = unsafe { f(self.as_mut_ptr()) };
+        match rv {
+            1 => Ok(()),
+            x if x < 0 => Err(TskitError::ErrorCode { code: x }),
+            _ => Err(TskitError::IndexError),
+        }
+    }
+
+    /// Return the id of the virtual root.
+    ///
+    /// The virtual root is the (conceptual) parent of all
+    /// roots of the tree.  Its id is equal to the number of
+    /// nodes in the node table.
+    ///
+    /// The virtual root is never returned by [`Tree::roots`]
+    /// or by node traversals, and the number of roots
+    /// reported by [`Tree::num_roots`] does not include it.
+    /// The children of the virtual root, as returned by
+    /// [`Tree::children`], are the roots of the tree.
+    ///
+    /// # Note
+    ///
+    /// The bundled `tskit` C library predates support for
+    /// virtual roots.  The value returned here is therefore
+    /// only a sentinel: the tree arrays contain no data for it.
+    /// Their length is the number of nodes in the node table,
+    /// which does not include the virtual root.
+    pub fn virtual_root(&self) -> tsk_id_t {
+        self.num_nodes as tsk_id_t
+    }
+
     /// Get the parent of node `u`.
     ///
+    /// The parent of the [virtual root](Tree::virtual_root)
+    /// is [`TSK_NULL`].
+    ///
     /// # Errors
     ///
     /// [`TskitError`] if `u` is out of range.
     pub fn parent(&self, u: tsk_id_t) -> Result<tsk_id_t, TskitError> {
+        if u == self.virtual_root() {
+            return Ok(TSK_NULL);
+        }
         unsafe_tsk_column_access!(u, 0, self.num_nodes, self.inner.parent)
     }
 
+    /// Get the root of the tree containing node `u`.
+    ///
+    /// If `u` is itself a root, `u` is returned.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range.
+    pub fn root_of(&self, u: tsk_id_t) -> Result<tsk_id_t, TskitError> {
+        let mut root = u;
+        let mut p = self.parent(u)?;
+        while p != TSK_NULL {
+            root = p;
+            p = self.parent(p)?;
+        }
+        Ok(root)
+    }
+
+    /// Get the id of the edge from node `u` to its parent
+    /// in the current tree.
+    ///
+    /// Returns `None` if `u` has no parent, which is the case for
+    /// roots and for nodes not in the current tree.
+    ///
+    /// # Note
+    ///
+    /// The bundled `tskit` C library does not record which edge
+    /// each branch comes from.  The edges are therefore found
+    /// via [`Tree::edges`], which takes time linear in the number
+    /// of edges.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range.
+    pub fn edge_to_parent(&self, u: tsk_id_t) -> Result<Option<tsk_id_t>, TskitError> {
+        let p = self.parent(u)?;
+        if p == TSK_NULL {
+            return Ok(None);
+        }
+        let (parent, child, _, _) = self.edge_columns();
+        Ok(self
+            .edges()
+            .find(|&e| child[e as usize] == u && parent[e as usize] == p))
+    }
+
+    /// Return an [`Iterator`] over the ids of the edges
+    /// in the current tree.
+    ///
+    /// These are the edges whose intervals contain [`Tree::interval`].
+    /// There is one such edge for each node with a parent.
+    /// The ids are returned in increasing order, which is the
+    /// order of the edge table.
+    ///
+    /// # Note
+    ///
+    /// The entire edge table is scanned.
+    pub fn edges(&self) -> impl Iterator<Item = tsk_id_t> + '_ {
+        let (_, _, left, right) = self.edge_columns();
+        let (tree_left, _) = self.interval();
+        (0..left.len())
+            .filter(move |&e| left[e] <= tree_left && tree_left < right[e])
+            .map(|e| e as tsk_id_t)
+    }
+
+    // Return the parent, child, left, and right columns of the edge table.
+    fn edge_columns(&self) -> (&[tsk_id_t], &[tsk_id_t], &[f64], &[f64]) {
+        let edges = unsafe { &(*(*(*self.as_ptr()).tree_sequence).tables).edges };
+        let num_edges = edges.num_rows as usize;
+        unsafe {
+            (
+                std::slice::from_raw_parts(edges.parent, num_edges),
+                std::slice::from_raw_parts(edges.child, num_edges),
+                std::slice::from_raw_parts(edges.left, num_edges),
+                std::slice::from_raw_parts(edges.right, num_edges),
+            )
+        }
+    }
+
+    /// Count the mutations above node `u` in the current tree.
+    ///
+    /// Only mutations at sites whose positions are in
+    /// [`Tree::interval`] are counted.
+    /// Returns `0` if there are no such mutations,
+    /// including when `u` is not in the current tree.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range.
+    pub fn num_mutations_on_branch(&self, u: tsk_id_t) -> Result<tsk_size_t, TskitError> {
+        unsafe_tsk_column_access!(u, 0, self.num_nodes, self.inner.parent)?;
+        if self.inner.sites_length == 0 {
+            return Ok(0);
+        }
+        let sites = unsafe {
+            std::slice::from_raw_parts(self.inner.sites, self.inner.sites_length as usize)
+        };
+        let mut count = 0;
+        for site in sites {
+            if site.mutations_length == 0 {
+                continue;
+            }
+            let mutations = unsafe {
+                std::slice::from_raw_parts(site.mutations, site.mutations_length as usize)
+            };
+            count += mutations.iter().filter(|m| m.node == u).count() as tsk_size_t;
+        }
+        Ok(count)
+    }
+
     /// Get the left child of node `u`.
     ///
     /// # Errors
@@ -406,9 +685,15 @@ impl Tree {
 
     /// Get the list of sample nodes as a slice.
     pub fn sample_nodes(&self) -> &[tsk_id_t] {
-        let num_samples =
-            unsafe { ll_bindings::tsk_treeseq_get_num_samples((*self.as_ptr()).tree_sequence) };
-        tree_array_slice!(self, samples, num_samples)
+        tree_array_slice!(self, samples, self.num_samples())
+    }
+
+    /// Get the total number of samples in the tree sequence.
+    ///
+    /// Unlike [`Tree::num_tracked_samples`], this value is
+    /// independent of the node and of the tracked samples.
+    pub fn num_samples(&self) -> tsk_size_t {
+        unsafe { ll_bindings::tsk_treeseq_get_num_samples((*self.as_ptr()).tree_sequence) }
     }
 
     /// Return an [`Iterator`] from the node `u` to the root of the tree.
@@ -436,18 +721,76 @@ impl Tree {
 
     /// Return an [`Iterator`] over the children of node `u`.
     ///
+    /// If `u` is the [virtual root](Tree::virtual_root),
+    /// the roots of the tree are returned.
+    ///
     /// # Errors
     ///
     /// [`TskitError::IndexError`] if `u` is out of range.
     pub fn children(&self, u: tsk_id_t) -> Result<impl Iterator<Item = tsk_id_t> + '_, TskitError> {
         ChildIterator::new(&self, u)
     }
+
+    /// Return an [`Iterator`] over the children of node `u`,
+    /// paired with the time of each child.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range.
+    pub fn children_with_time(
+        &self,
+        u: tsk_id_t,
+    ) -> Result<impl Iterator<Item = (tsk_id_t, f64)> + '_, TskitError> {
+        let nodes = unsafe { &(*(*(*self.as_ptr()).tree_sequence).tables).nodes };
+        let time = unsafe { std::slice::from_raw_parts(nodes.time, nodes.num_rows as usize) };
+        Ok(self.children(u)?.map(move |c| (c, time[c as usize])))
+    }
+
+    /// Fill `buf` with the children of node `u`.
+    ///
+    /// The contents of `buf` are cleared first.
+    /// The children are in the same order as [`Tree::children`].
+    /// Reusing `buf` avoids allocating a new vector for each node.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range,
+    /// in which case `buf` is not modified.
+    pub fn collect_children_into(
+        &self,
+        u: tsk_id_t,
+        buf: &mut Vec<tsk_id_t>,
+    ) -> Result<(), TskitError> {
+        let children = self.children(u)?;
+        buf.clear();
+        buf.extend(children);
+        Ok(())
+    }
+
+    /// Return `true` if node `u` is a sample.
+    ///
+    /// Unlike [`Tree::samples`], this function does not
+    /// require [`TreeFlags::SAMPLE_LISTS`].
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range.
+    pub fn is_sample(&self, u: tsk_id_t) -> Result<bool, TskitError> {
+        if u < 0 || u >= self.num_nodes as tsk_id_t {
+            return Err(TskitError::IndexError);
+        }
+        Ok(unsafe { ll_bindings::tsk_tree_is_sample(self.as_ptr(), u) })
+    }
+
     /// Return an [`Iterator`] over the sample nodes descending from node `u`.
     ///
     /// # Note
     ///
     /// If `u` is itself a sample, then it is included in the values returned.
     ///
+    /// To test if a single node is a sample, [`Tree::is_sample`]
+    /// does not require [`TreeFlags::SAMPLE_LISTS`].
+    ///
     /// # Errors
     ///
     /// [`TskitError::IndexError`] if `u` is out of range.
@@ -468,6 +811,23 @@ impl Tree {
         RootIterator::new(self)
     }
 
+    /// Return the number of roots of the tree.
+    pub fn num_roots(&self) -> tsk_size_t {
+        unsafe { ll_bindings::tsk_tree_get_num_roots(self.as_ptr()) }
+    }
+
+    /// Return `true` if the tree has a single root.
+    ///
+    /// # Note
+    ///
+    /// A tree with more than one root is not fully coalesced:
+    /// the ancestry of at least two samples does not share
+    /// a common ancestor.  A tree with no roots (no samples)
+    /// is also not coalesced.
+    pub fn is_coalesced(&self) -> bool {
+        self.num_roots() == 1
+    }
+
     /// Return all roots as a vector.
     pub fn roots_to_vec(&self) -> Vec<tsk_id_t> {
         let mut v = vec![];
@@ -495,6 +855,24 @@ impl Tree {
         }
     }
 
+    /// Return an [`Iterator`] over the sample nodes of the tree.
+    ///
+    /// The nodes are visited in the same order as
+    /// [`Tree::traverse_nodes`], skipping nodes that are
+    /// not samples.
+    ///
+    /// # Parameters
+    ///
+    /// * `order`: A value from [`NodeTraversalOrder`] specifying the
+    ///   iteration order.
+    pub fn sample_traversal(
+        &self,
+        order: NodeTraversalOrder,
+    ) -> impl Iterator<Item = tsk_id_t> + '_ {
+        self.traverse_nodes(order)
+            .filter(move |&u| self.is_sample(u).unwrap_or(false))
+    }
+
     /// Return the [`crate::NodeTable`] for this current tree
     /// (and the tree sequence from which it came).
     ///
@@ -530,18 +908,95 @@ impl Tree {
         }
     }
 
+    /// Calculate the sum, over all branches, of the branch length
+    /// multiplied by `f * (1 - f)`, where `f` is the fraction of
+    /// the tracked samples below the branch.
+    ///
+    /// This is the contribution of this tree to branch-based
+    /// diversity, ignoring the tree's span.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::NotTrackingSamples`] if [`TreeFlags::NO_SAMPLE_COUNTS`]
+    ///   was used to initialize `self`.
+    pub fn diversity_contribution(&self) -> Result<f64, TskitError> {
+        if self.flags.contains(TreeFlags::NO_SAMPLE_COUNTS) {
+            return Err(TskitError::NotTrackingSamples);
+        }
+        let mut total_samples = 0;
+        for r in self.roots() {
+            total_samples += self.num_tracked_samples(r)?;
+        }
+        if total_samples == 0 {
+            return Ok(0.);
+        }
+        let nt = self.node_table();
+        let mut d = 0.;
+        for n in self.traverse_nodes(NodeTraversalOrder::Preorder) {
+            let p = self.parent(n)?;
+            if p != TSK_NULL {
+                let f = self.num_tracked_samples(n)? as f64 / total_samples as f64;
+                d += (nt.time(p)? - nt.time(n)?) * f * (1. - f);
+            }
+        }
+        Ok(d)
+    }
+
     /// Get the number of samples below node `u`.
     ///
     /// # Errors
     ///
-    /// * [`TskitError`] if [`TreeFlags::NO_SAMPLE_COUNTS`].
+    /// * [`TskitError::NotTrackingSamples`] if [`TreeFlags::NO_SAMPLE_COUNTS`]
+    ///   was used to initialize `self`.
+    /// * [`TskitError`] if `u` is out of range.
     pub fn num_tracked_samples(&self, u: tsk_id_t) -> Result<u64, TskitError> {
+        if self.flags.contains(TreeFlags::NO_SAMPLE_COUNTS) {
+            return Err(TskitError::NotTrackingSamples);
+        }
         let mut n = u64::MAX;
         let np: *mut u64 = &mut n;
         let code = unsafe { ll_bindings::tsk_tree_get_num_tracked_samples(self.as_ptr(), u, np) };
         handle_tsk_return_value!(code, n)
     }
 
+    /// Set the samples counted by [`Tree::num_tracked_samples`].
+    ///
+    /// By default, all samples are tracked.
+    /// The tracked samples remain in effect as the tree
+    /// is moved along the tree sequence.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::NotTrackingSamples`] if [`TreeFlags::NO_SAMPLE_COUNTS`]
+    ///   was used to initialize `self`.
+    /// * [`TskitError::ValueError`] if any id is not a sample node.
+    /// * [`TskitError::ErrorCode`] if `samples` contains duplicates.
+    ///
+    /// If an error is returned, the tracked sample counts are
+    /// unspecified until this function is called successfully.
+    pub fn set_tracked_samples(&mut self, samples: &[tsk_id_t]) -> Result<(), TskitError> {
+        if self.flags.contains(TreeFlags::NO_SAMPLE_COUNTS) {
+            return Err(TskitError::NotTrackingSamples);
+        }
+        crate::util::validate_sample_ids(samples, self.num_nodes)?;
+        for &s in samples {
+            if !self.is_sample(s)? {
+                return Err(TskitError::ValueError {
+                    got: format!("non-sample node {}", s),
+                    expected: String::from("sample node"),
+                });
+            }
+        }
+        let rv = unsafe {
+            ll_bindings::tsk_tree_set_tracked_samples(
+                self.as_mut_ptr(),
+                samples.len() as ll_bindings::size_t,
+                samples.as_ptr(),
+            )
+        };
+        handle_tsk_return_value!(rv, ())
+    }
+
     /// Calculate the average Kendall-Colijn (`K-C`) distance between
     /// pairs of trees whose intervals overlap.
     ///
@@ -562,66 +1017,316 @@ impl Tree {
         };
         handle_tsk_return_value!(code, kc)
     }
-}
 
-impl streaming_iterator::StreamingIterator for Tree {
-    type Item = Tree;
-    fn advance(&mut self) {
-        let rv = if self.current_tree == 0 {
-            unsafe { ll_bindings::tsk_tree_first(self.as_mut_ptr()) }
-        } else {
-            unsafe { ll_bindings::tsk_tree_next(self.as_mut_ptr()) }
-        };
-        if rv == 0 {
-            self.advanced = false;
-            self.current_tree += 1;
-        } else if rv == 1 {
-            self.advanced = true;
-            self.current_tree += 1;
-        } else if rv < 0 {
-            panic_on_tskit_error!(rv);
+    /// Calculate the Sackin index of the tree.
+    ///
+    /// The Sackin index is the sum of the depths of all leaves,
+    /// where depth is the number of edges between a leaf and its root.
+    /// For trees with multiple roots, the values for each
+    /// root are summed.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] may be returned if a node index is out of range.
+    pub fn sackin_index(&self) -> Result<u64, TskitError> {
+        let mut stack: Vec<(tsk_id_t, u64)> = self.roots().map(|r| (r, 0)).collect();
+        let mut index = 0;
+        while let Some((u, depth)) = stack.pop() {
+            let mut is_leaf = true;
+            for c in self.children(u)? {
+                is_leaf = false;
+                stack.push((c, depth + 1));
+            }
+            if is_leaf {
+                index += depth;
+            }
         }
+        Ok(index)
     }
 
-    fn get(&self) -> Option<&Tree> {
-        match self.advanced {
-            true => Some(&self),
-            false => None,
+    /// Calculate the Colless index of the tree.
+    ///
+    /// The Colless index is the sum, over all internal nodes,
+    /// of the absolute difference in the number of leaves
+    /// descending from the left and right children.
+    /// For trees with multiple roots, the values for each
+    /// root are summed.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`] if any internal node does not
+    /// have exactly two children.
+    pub fn colless_index(&self) -> Result<u64, TskitError> {
+        let num_leaves = self.subtree_sizes()?;
+        let mut index = 0;
+        for u in self.traverse_nodes(NodeTraversalOrder::Preorder) {
+            let left = self.left_child(u)?;
+            if left == TSK_NULL {
+                continue;
+            }
+            let right = self.right_child(u)?;
+            if left == right || self.right_sib(left)? != right {
+                return Err(TskitError::ValueError {
+                    got: format!("non-binary node {}", u),
+                    expected: String::from("a binary tree"),
+                });
+            }
+            let nl = num_leaves[left as usize] as u64;
+            let nr = num_leaves[right as usize] as u64;
+            index += nl.abs_diff(nr);
         }
+        Ok(index)
     }
-}
 
-impl streaming_iterator::DoubleEndedStreamingIterator for Tree {
-    fn advance_back(&mut self) {
-        let rv = if self.current_tree == 0 {
-            unsafe { ll_bindings::tsk_tree_last(self.as_mut_ptr()) }
-        } else {
-            unsafe { ll_bindings::tsk_tree_prev(self.as_mut_ptr()) }
-        };
-        if rv == 0 {
-            self.advanced = false;
-            self.current_tree -= 1;
-        } else if rv == 1 {
-            self.advanced = true;
-            self.current_tree -= 1;
-        } else if rv < 0 {
-            panic_on_tskit_error!(rv);
+    /// Return the number of leaves descending from each node.
+    ///
+    /// The return value is indexed by node id.
+    /// A leaf counts itself, so its value is `1`.
+    /// Nodes that are not in the current tree have a value of `0`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] may be returned if a node index is out of range.
+    pub fn subtree_sizes(&self) -> Result<Vec<tsk_size_t>, TskitError> {
+        let nodes = self
+            .traverse_nodes(NodeTraversalOrder::Preorder)
+            .collect::<Vec<_>>();
+        let mut sizes = vec![0; self.num_nodes as usize];
+        // Reverse preorder visits children before parents.
+        for &u in nodes.iter().rev() {
+            if self.left_child(u)? == TSK_NULL {
+                sizes[u as usize] = 1;
+            }
+            let p = self.parent(u)?;
+            if p != TSK_NULL {
+                sizes[p as usize] += sizes[u as usize];
+            }
         }
+        Ok(sizes)
     }
-}
-
-/// Specify the traversal order used by
-/// [`Tree::traverse_nodes`].
-pub enum NodeTraversalOrder {
-    ///Preorder traversal, starting at the root(s) of a [`Tree`].
-    ///For trees with multiple roots, start at the left root,
-    ///traverse to tips, proceeed to the next root, etc..
-    Preorder,
-}
 
-struct PreorderNodeIterator<'a> {
-    root_stack: Vec<i32>,
-    node_stack: Vec<i32>,
+    /// Return the nodes of the tree in postorder.
+    ///
+    /// Children are visited before their parents, and the
+    /// subtrees of the roots are visited from left to right.
+    /// Within each subtree, children are visited from left to right.
+    ///
+    /// # Note
+    ///
+    /// Each call traverses the tree and allocates a new vector
+    /// with one entry per node in the tree.  Callers that need
+    /// the order more than once for the same tree should keep
+    /// the return value.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] may be returned if a node index is out of range.
+    pub fn postorder(&self) -> Result<Vec<tsk_id_t>, TskitError> {
+        let mut nodes = vec![];
+        let mut stack = vec![];
+        for root in self.roots() {
+            let start = nodes.len();
+            stack.push(root);
+            // A preorder visiting the rightmost child first
+            // is the reverse of a postorder.
+            while let Some(u) = stack.pop() {
+                nodes.push(u);
+                let mut c = self.left_child(u)?;
+                while c != TSK_NULL {
+                    stack.push(c);
+                    c = self.right_sib(c)?;
+                }
+            }
+            nodes[start..].reverse();
+        }
+        Ok(nodes)
+    }
+
+    /// Write the tree in Newick format, followed by a newline.
+    ///
+    /// Leaves are labelled by their node id plus one, and branch
+    /// lengths are written with `precision` digits after the
+    /// decimal point.
+    /// The text is written directly to `w`, making it possible to
+    /// write many trees to a file without an intermediate `String`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `precision` is negative or if
+    ///   the tree does not have exactly one root.
+    /// * [`TskitError::IOError`] if writing to `w` fails.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 1.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_edge(0., 100., 0, 1).unwrap();
+    /// tables.add_edge(0., 100., 0, 2).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let tree = treeseq.first_tree(tskit::TreeFlags::default()).unwrap();
+    /// let mut buffer = vec![];
+    /// tree.write_newick(&mut buffer, 1).unwrap();
+    /// assert_eq!(buffer, b"(2:1.0,3:1.0);\n");
+    /// ```
+    pub fn write_newick<W: std::io::Write>(
+        &self,
+        w: &mut W,
+        precision: i32,
+    ) -> Result<(), TskitError> {
+        if precision < 0 {
+            return Err(TskitError::ValueError {
+                got: format!("{}", precision),
+                expected: String::from("precision >= 0"),
+            });
+        }
+        if self.num_roots() != 1 {
+            return Err(TskitError::ValueError {
+                got: format!("{} roots", self.num_roots()),
+                expected: String::from("1 root"),
+            });
+        }
+        let root = unsafe { (*self.as_ptr()).left_root };
+        // A rough guess, doubled until the text fits.
+        let mut buffer: Vec<u8> =
+            vec![0; (self.num_nodes as usize + 1) * (precision as usize + 16)];
+        loop {
+            let rv = unsafe {
+                ll_bindings::tsk_convert_newick(
+                    self.as_ptr(),
+                    root,
+                    precision as ll_bindings::size_t,
+                    0,
+                    buffer.len() as ll_bindings::size_t,
+                    buffer.as_mut_ptr() as *mut libc::c_char,
+                )
+            };
+            if rv == ll_bindings::TSK_ERR_BUFFER_OVERFLOW {
+                let len = 2 * buffer.len();
+                buffer.resize(len, 0);
+                continue;
+            }
+            if rv < 0 {
+                return Err(TskitError::ErrorCode { code: rv });
+            }
+            break;
+        }
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        w.write_all(&buffer[..len])?;
+        w.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Return the most recent common ancestor (MRCA) of
+    /// each pair of samples.
+    ///
+    /// The samples are ordered as in [`Tree::sample_nodes`].
+    /// With `n` samples, the return value is a flattened
+    /// `n` by `n` matrix in row-major order: the MRCA
+    /// of samples `i` and `j` is at index `i * n + j`.
+    /// The matrix is symmetric, the diagonal contains the samples
+    /// themselves, and samples with no common ancestor in this
+    /// tree have a value of [`TSK_NULL`].
+    ///
+    /// The matrix is filled in one traversal of the tree,
+    /// merging the sets of samples below each node into those
+    /// of its parent.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] may be returned if a node index is out of range.
+    pub fn sample_mrca_matrix(&self) -> Result<Vec<tsk_id_t>, TskitError> {
+        let samples = self.sample_nodes();
+        let n = samples.len();
+        let mut matrix = vec![TSK_NULL; n * n];
+        let mut below: Vec<Vec<usize>> = vec![vec![]; self.num_nodes as usize];
+        for (i, &s) in samples.iter().enumerate() {
+            matrix[i * n + i] = s;
+            below[s as usize].push(i);
+        }
+        let nodes = self
+            .traverse_nodes(NodeTraversalOrder::Preorder)
+            .collect::<Vec<_>>();
+        // Reverse preorder visits children before parents.
+        for &u in nodes.iter().rev() {
+            let p = self.parent(u)?;
+            if p != TSK_NULL {
+                let child = std::mem::take(&mut below[u as usize]);
+                for &a in below[p as usize].iter() {
+                    for &b in child.iter() {
+                        matrix[a * n + b] = p;
+                        matrix[b * n + a] = p;
+                    }
+                }
+                below[p as usize].extend(child);
+            }
+        }
+        Ok(matrix)
+    }
+}
+
+impl streaming_iterator::StreamingIterator for Tree {
+    type Item = Tree;
+    fn advance(&mut self) {
+        let rv = if self.current_tree == 0 {
+            unsafe { ll_bindings::tsk_tree_first(self.as_mut_ptr()) }
+        } else {
+            unsafe { ll_bindings::tsk_tree_next(self.as_mut_ptr()) }
+        };
+        if rv == 0 {
+            self.advanced = false;
+            self.current_tree += 1;
+        } else if rv == 1 {
+            self.advanced = true;
+            self.current_tree += 1;
+        } else if rv < 0 {
+            panic_on_tskit_error!(rv);
+        }
+    }
+
+    fn get(&self) -> Option<&Tree> {
+        match self.advanced {
+            true => Some(&self),
+            false => None,
+        }
+    }
+}
+
+impl streaming_iterator::DoubleEndedStreamingIterator for Tree {
+    fn advance_back(&mut self) {
+        let rv = if self.current_tree == 0 {
+            unsafe { ll_bindings::tsk_tree_last(self.as_mut_ptr()) }
+        } else {
+            unsafe { ll_bindings::tsk_tree_prev(self.as_mut_ptr()) }
+        };
+        if rv == 0 {
+            self.advanced = false;
+            self.current_tree -= 1;
+        } else if rv == 1 {
+            self.advanced = true;
+            self.current_tree -= 1;
+        } else if rv < 0 {
+            panic_on_tskit_error!(rv);
+        }
+    }
+}
+
+/// Specify the traversal order used by
+/// [`Tree::traverse_nodes`].
+pub enum NodeTraversalOrder {
+    ///Preorder traversal, starting at the root(s) of a [`Tree`].
+    ///For trees with multiple roots, start at the left root,
+    ///traverse to tips, proceeed to the next root, etc..
+    Preorder,
+}
+
+struct PreorderNodeIterator<'a> {
+    root_stack: Vec<i32>,
+    node_stack: Vec<i32>,
     tree: &'a Tree,
     current_node_: Option<tsk_id_t>,
 }
@@ -645,20 +1350,16 @@ impl<'a> PreorderNodeIterator<'a> {
 impl NodeIterator for PreorderNodeIterator<'_> {
     fn next_node(&mut self) {
         self.current_node_ = self.node_stack.pop();
-        match self.current_node_ {
-            Some(u) => {
-                let mut c = self.tree.left_child(u).unwrap();
-                while c != TSK_NULL {
-                    self.node_stack.push(c);
-                    c = self.tree.right_sib(c).unwrap();
-                }
-            }
-            None => {
-                if let Some(r) = self.root_stack.pop() {
-                    self.current_node_ = Some(r);
-                }
+        if self.current_node_.is_none() {
+            self.current_node_ = self.root_stack.pop();
+        }
+        if let Some(u) = self.current_node_ {
+            let mut c = self.tree.left_child(u).unwrap();
+            while c != TSK_NULL {
+                self.node_stack.push(c);
+                c = self.tree.right_sib(c).unwrap();
             }
-        };
+        }
     }
 
     fn current_node(&mut self) -> Option<tsk_id_t> {
@@ -712,7 +1413,11 @@ struct ChildIterator<'a> {
 
 impl<'a> ChildIterator<'a> {
     fn new(tree: &'a Tree, u: tsk_id_t) -> Result<Self, TskitError> {
-        let c = tree.left_child(u)?;
+        let c = if u == tree.virtual_root() {
+            tree.inner.left_root
+        } else {
+            tree.left_child(u)?
+        };
 
         Ok(ChildIterator {
             current_child: None,
@@ -833,6 +1538,82 @@ impl NodeIterator for SamplesIterator<'_> {
 
 iterator_for_nodeiterator!(SamplesIterator<'_>);
 
+/// Summary of a single tree, as returned by [`TreeSequence::trees`].
+///
+/// Only small, per-tree values are copied out of the
+/// underlying [`Tree`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeInfo {
+    /// Index of the tree in the tree sequence.
+    pub index: tsk_id_t,
+    /// The genomic interval `[left, right)` covered by the tree.
+    pub interval: (f64, f64),
+    /// The roots of the tree, from left to right.
+    pub roots: Vec<tsk_id_t>,
+    /// The number of sites in the tree.
+    pub num_sites: tsk_size_t,
+}
+
+impl TreeInfo {
+    fn new(tree: &Tree) -> Self {
+        Self {
+            index: tree.inner.index,
+            interval: tree.interval(),
+            roots: tree.roots_to_vec(),
+            num_sites: tree.inner.sites_length,
+        }
+    }
+
+    /// Length of the genomic interval covered by the tree.
+    pub fn span(&self) -> f64 {
+        self.interval.1 - self.interval.0
+    }
+}
+
+/// A copy of the topology of a single tree, as returned by
+/// [`TreeSequence::tree_snapshots`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeSnapshot {
+    /// The genomic interval `[left, right)` covered by the tree.
+    pub interval: (f64, f64),
+    /// The parent of each node, indexed by node id.
+    pub parents: Vec<tsk_id_t>,
+    /// The roots of the tree, from left to right.
+    pub roots: Vec<tsk_id_t>,
+}
+
+impl TreeSnapshot {
+    fn new(tree: &Tree) -> Self {
+        Self {
+            interval: tree.interval(),
+            parents: tree.parent_array().to_vec(),
+            roots: tree.roots_to_vec(),
+        }
+    }
+
+    /// Return the parents as a [`crate::NodeArray`].
+    pub fn parent_node_array(&self) -> crate::NodeArray<'_> {
+        crate::NodeArray::from(self.parents.as_slice())
+    }
+}
+
+/// An [`Iterator`] over the trees of a [`TreeSequence`].
+///
+/// See [`TreeSequence::trees`].
+pub struct TreesIterator<'a> {
+    tree: Tree,
+    treeseq: std::marker::PhantomData<&'a TreeSequence>,
+}
+
+impl<'a> Iterator for TreesIterator<'a> {
+    type Item = TreeInfo;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use streaming_iterator::StreamingIterator;
+        self.tree.next().map(TreeInfo::new)
+    }
+}
+
 /// A tree sequence.
 ///
 /// This is a thin wrapper around the C type `tsk_treeseq_t`.
@@ -856,12 +1637,44 @@ iterator_for_nodeiterator!(SamplesIterator<'_>);
 /// // tables gets moved into our treeseq variable:
 /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
 /// ```
+///
+/// # Thread safety
+///
+/// `TreeSequence` is [`Send`], so it may be moved to another
+/// thread.  It is not [`Sync`]: the [`NodeTable`] returned by
+/// [`TableAccess::nodes`] or [`Tree::node_table`] allows writing
+/// node flags and times via `&self`.
+///
+/// To iterate over trees or calculate statistics in parallel,
+/// give each thread its own copy:
+///
+/// ```
+/// # let mut tables = tskit::TableCollection::new(1000.).unwrap();
+/// # tables.add_node(0, 1.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+/// # tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+/// # tables.add_edge(0., 1000., 0, 1).unwrap();
+/// # tables.build_index().unwrap();
+/// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+/// let copy = treeseq
+///     .dump_tables()
+///     .unwrap()
+///     .tree_sequence(tskit::TreeSequenceFlags::default())
+///     .unwrap();
+/// let handle = std::thread::spawn(move || copy.num_trees());
+/// assert_eq!(handle.join().unwrap(), treeseq.num_trees());
+/// ```
 pub struct TreeSequence {
     inner: Box<ll_bindings::tsk_treeseq_t>,
 }
 
 build_tskit_type!(TreeSequence, ll_bindings::tsk_treeseq_t, tsk_treeseq_free);
 
+// SAFETY: a TreeSequence owns all of its memory, and
+// views of it (tables, trees) borrow from it, so moving it
+// to another thread is sound.  Sync is not implemented
+// because the NodeTable views obtained via &self allow writes.
+unsafe impl Send for TreeSequence {}
+
 impl TreeSequence {
     /// Create a tree sequence from a [`TableCollection`].
     /// In general, [`TableCollection::tree_sequence`] may be preferred.
@@ -951,6 +1764,45 @@ impl TreeSequence {
         handle_tsk_return_value!(rv, copy)
     }
 
+    /// Return an [`Iterator`] over the edges removed and
+    /// inserted at each transition between trees.
+    ///
+    /// There is one item per tree.  The edges removed at the
+    /// right end of the last tree are not reported.
+    pub fn edge_differences_iter(&self) -> crate::EdgeDiffsIterator<'_> {
+        crate::EdgeDiffsIterator::new(unsafe { &(*self.inner.tables) })
+    }
+
+    /// Return immutable views of all tables.
+    ///
+    /// See [`crate::TableViews`] for details.
+    pub fn tables(&self) -> crate::TableViews<'_> {
+        crate::TableViews::new_from_tables(unsafe { &(*self.inner.tables) })
+    }
+
+    /// Consume the tree sequence, returning its [`TableCollection`].
+    ///
+    /// Unlike [`TreeSequence::dump_tables`], the tables are moved
+    /// out of `self` rather than copied.
+    ///
+    /// # Errors
+    ///
+    /// Currently, this function does not fail.  The return type
+    /// allows for errors in future implementations.
+    pub fn into_tables(mut self) -> Result<TableCollection, TskitError> {
+        let mut tables = TableCollection::wrap();
+        unsafe {
+            std::ptr::copy_nonoverlapping(self.inner.tables, tables.as_mut_ptr(), 1);
+            // The C library allocated the tables struct itself.
+            // Its contents now belong to `tables`, so we free the
+            // struct and null the pointer so that tsk_treeseq_free
+            // does not free the contents.
+            libc::free(self.inner.tables as *mut libc::c_void);
+            self.inner.tables = std::ptr::null_mut();
+        }
+        Ok(tables)
+    }
+
     /// Create an iterator over trees.
     ///
     /// # Parameters
@@ -959,6 +1811,10 @@ impl TreeSequence {
     ///
     /// # Errors
     ///
+    /// * [`TskitError::ValueError`] if `flags` contains both
+    ///   [`TreeFlags::SAMPLE_LISTS`] and [`TreeFlags::NO_SAMPLE_COUNTS`].
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    ///
     /// # Examples
     ///
     /// ```
@@ -997,382 +1853,2336 @@ impl TreeSequence {
         Ok(tree)
     }
 
-    /// Get the list of samples as a vector.
-    #[deprecated(
-        since = "0.2.3",
-        note = "Please use TreeSequence::sample_nodes instead"
-    )]
-    pub fn samples_to_vec(&self) -> Vec<tsk_id_t> {
-        let num_samples = unsafe { ll_bindings::tsk_treeseq_get_num_samples(self.as_ptr()) };
-        let mut rv = vec![];
+    /// Obtain a [`Tree`] for iterating over the topology of each tree.
+    ///
+    /// The tree is created with [`TreeFlags::NO_SAMPLE_COUNTS`],
+    /// which skips updating the number of samples below each node.
+    /// This is faster than [`TreeSequence::tree_iterator`] when only
+    /// the parent and child arrays are needed.
+    ///
+    /// # Note
+    ///
+    /// The following do not work on the resulting trees:
+    ///
+    /// * [`Tree::samples`] and [`Tree::num_tracked_samples`].
+    /// * [`Tree::roots`], [`Tree::num_roots`], and anything that
+    ///   depends on them, such as [`Tree::traverse_nodes`].
+    ///   The bundled `tskit` C library uses the sample counts
+    ///   to track the roots.
+    ///   For the same reason, roots are not linked to each other
+    ///   via the sib arrays.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if the underlying [`Tree`] cannot be created.
+    pub fn topology_iterator(&self) -> Result<Tree, TskitError> {
+        self.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS)
+    }
 
-        for i in 0..num_samples {
-            let u = unsafe { *(*self.as_ptr()).samples.offset(i as isize) };
-            rv.push(u);
-        }
-        rv
+    /// Return a [`Tree`] positioned at the leftmost tree.
+    ///
+    /// The returned tree may be advanced further using
+    /// [`streaming_iterator::StreamingIterator`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if there are no trees.
+    /// * [`TskitError`] if the underlying [`Tree`] cannot be created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let tree = treeseq.first_tree(tskit::TreeFlags::default()).unwrap();
+    /// assert_eq!(tree.interval(), (0., 1000.));
+    /// ```
+    pub fn first_tree(&self, flags: TreeFlags) -> Result<Tree, TskitError> {
+        use streaming_iterator::StreamingIterator;
+        let mut tree = self.tree_iterator(flags)?;
+        tree.advance();
+        Self::ensure_tree_advanced(tree)
     }
 
-    /// Get the list of sample nodes as a slice.
-    pub fn sample_nodes(&self) -> &[tsk_id_t] {
-        let num_samples = unsafe { ll_bindings::tsk_treeseq_get_num_samples(self.as_ptr()) };
-        tree_array_slice!(self, samples, num_samples)
+    /// Return a [`Tree`] positioned at the rightmost tree.
+    ///
+    /// The returned tree may be moved leftwards using
+    /// [`streaming_iterator::DoubleEndedStreamingIterator`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if there are no trees.
+    /// * [`TskitError`] if the underlying [`Tree`] cannot be created.
+    pub fn last_tree(&self, flags: TreeFlags) -> Result<Tree, TskitError> {
+        use streaming_iterator::DoubleEndedStreamingIterator;
+        let mut tree = self.tree_iterator(flags)?;
+        tree.advance_back();
+        Self::ensure_tree_advanced(tree)
     }
 
-    /// Get the number of trees.
-    pub fn num_trees(&self) -> tsk_size_t {
-        unsafe { ll_bindings::tsk_treeseq_get_num_trees(self.as_ptr()) }
+    fn ensure_tree_advanced(tree: Tree) -> Result<Tree, TskitError> {
+        match tree.advanced {
+            true => Ok(tree),
+            false => Err(TskitError::ValueError {
+                got: String::from("0 trees"),
+                expected: String::from("at least 1 tree"),
+            }),
+        }
     }
 
-    /// Calculate the average Kendall-Colijn (`K-C`) distance between
-    /// pairs of trees whose intervals overlap.
-    ///
-    /// # Note
+    /// Calculate the mean of a per-tree quantity, weighted by
+    /// the span of each tree.
     ///
-    /// * [Citation](https://doi.org/10.1093/molbev/msw124)
+    /// This function creates and advances its own [`Tree`],
+    /// using `flags`, visiting each tree from left to right.
     ///
     /// # Parameters
     ///
-    /// * `lambda` specifies the relative weight of topology and branch length.
-    ///    See [`Tree::kc_distance`] for more details.
-    pub fn kc_distance(&self, other: &TreeSequence, lambda: f64) -> Result<f64, TskitError> {
-        let mut kc: f64 = f64::NAN;
-        let kcp: *mut f64 = &mut kc;
-        let code = unsafe {
-            ll_bindings::tsk_treeseq_kc_distance(self.as_ptr(), other.as_ptr(), lambda, kcp)
-        };
-        handle_tsk_return_value!(code, kc)
+    /// * `flags`: the [`TreeFlags`] used to initialize the tree.
+    /// * `f`: a function returning the value for a tree.
+    ///
+    /// # Errors
+    ///
+    /// * Any error returned by `f`.
+    /// * [`TskitError`] if the underlying [`Tree`] cannot be created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 1.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_edge(0., 50., 0, 1).unwrap();
+    /// tables.add_edge(0., 50., 0, 2).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let mean = treeseq
+    ///     .span_weighted_mean(tskit::TreeFlags::default(), |t| t.total_branch_length(false))
+    ///     .unwrap();
+    /// // The first half of the genome has a total branch length of 2.
+    /// assert_eq!(mean, 1.);
+    /// ```
+    pub fn span_weighted_mean<F>(&self, flags: TreeFlags, mut f: F) -> Result<f64, TskitError>
+    where
+        F: FnMut(&Tree) -> Result<f64, TskitError>,
+    {
+        use streaming_iterator::StreamingIterator;
+        let mut tree = self.tree_iterator(flags)?;
+        let mut sum = 0.;
+        while let Some(t) = tree.next() {
+            sum += f(t)? * t.span();
+        }
+        Ok(sum / unsafe { (*self.inner.tables).sequence_length })
     }
 
-    // FIXME: document
-    pub fn num_samples(&self) -> tsk_size_t {
-        unsafe { ll_bindings::tsk_treeseq_get_num_samples(self.as_ptr()) }
+    /// Calculate the mean number of roots per tree, weighted
+    /// by the span of each tree.
+    ///
+    /// A value of `1.0` means that every tree has fully coalesced.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if the underlying [`Tree`] cannot be created.
+    pub fn mean_num_roots(&self) -> Result<f64, TskitError> {
+        self.span_weighted_mean(TreeFlags::default(), |t| Ok(t.num_roots() as f64))
     }
 
-    /// Simplify tables and return a new tree sequence.
+    /// Calculate the total span of the genome over which
+    /// `node` is ancestral to at least one sample.
     ///
-    /// # Parameters
+    /// The span of each tree where [`Tree::num_tracked_samples`]
+    /// is nonzero for `node` is summed.
+    /// A sample node is ancestral to itself.
     ///
-    /// * `samples`: a slice containing non-null node ids.
-    ///   The tables are simplified with respect to the ancestry
-    ///   of these nodes.
-    /// * `options`: A [`SimplificationOptions`] bit field controlling
-    ///   the behavior of simplification.
-    /// * `idmap`: if `true`, the return value contains a vector equal
-    ///   in length to the input node table.  For each input node,
-    ///   this vector either contains the node's new index or [`TSK_NULL`]
-    ///   if the input node is not part of the simplified history.
-    pub fn simplify(
-        &self,
-        samples: &[tsk_id_t],
-        options: SimplificationOptions,
-        idmap: bool,
-    ) -> Result<(Self, Option<Vec<tsk_id_t>>), TskitError> {
-        let mut tables = TableCollection::new(unsafe { (*self.inner.tables).sequence_length })?;
-        tables.build_index().unwrap();
-        let mut ts = tables.tree_sequence(TreeSequenceFlags::default())?;
-        let mut output_node_map: Vec<tsk_id_t> = vec![];
-        if idmap {
-            output_node_map.resize(self.nodes().num_rows() as usize, TSK_NULL);
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if `node` is not a valid node id.
+    /// * [`TskitError`] if the underlying [`Tree`] cannot be created.
+    pub fn ancestral_span(&self, node: tsk_id_t) -> Result<f64, TskitError> {
+        use streaming_iterator::StreamingIterator;
+        if node < 0 || node as tsk_size_t >= self.nodes().num_rows() {
+            return Err(TskitError::IndexError);
         }
-        let rv = unsafe {
-            ll_bindings::tsk_treeseq_simplify(
-                self.as_ptr(),
-                samples.as_ptr(),
-                samples.len() as tsk_size_t,
-                options.bits(),
-                ts.as_mut_ptr(),
-                match idmap {
-                    true => output_node_map.as_mut_ptr(),
-                    false => std::ptr::null_mut(),
-                },
-            )
-        };
-        handle_tsk_return_value!(
-            rv,
-            (
-                ts,
-                match idmap {
-                    true => Some(output_node_map),
-                    false => None,
+        let mut tree = self.tree_iterator(TreeFlags::default())?;
+        let mut span = 0.;
+        while let Some(t) = tree.next() {
+            if t.num_tracked_samples(node)? > 0 {
+                span += t.span();
+            }
+        }
+        Ok(span)
+    }
+
+    /// Return a short summary of the tree sequence as a JSON string.
+    ///
+    /// This is intended for quick inspection and dashboards,
+    /// and is not a serialization of the tree sequence.
+    /// The JSON object contains:
+    ///
+    /// * `sequence_length`
+    /// * `num_trees`, `num_samples`, `num_nodes`, `num_edges`,
+    ///   `num_sites`, and `num_mutations`
+    /// * `breakpoints`: see [`TreeSequence::breakpoints`]
+    /// * `trees`: an array with one object per tree, containing
+    ///   `left`, `right`, and `roots`.
+    ///
+    /// This function is enabled by the `"json"` feature.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if the trees cannot be iterated or if
+    /// the summary cannot be serialized.
+    #[cfg(feature = "json")]
+    pub fn to_json_summary(&self) -> Result<String, TskitError> {
+        let trees = self
+            .trees(TreeFlags::default())?
+            .map(|t| {
+                serde_json::json!({
+                    "left": t.interval.0,
+                    "right": t.interval.1,
+                    "roots": t.roots,
+                })
+            })
+            .collect::<Vec<_>>();
+        let summary = serde_json::json!({
+            "sequence_length": unsafe { (*self.inner.tables).sequence_length },
+            "num_trees": self.num_trees(),
+            "num_samples": self.num_samples(),
+            "num_nodes": self.nodes().num_rows(),
+            "num_edges": self.edges().num_rows(),
+            "num_sites": self.sites().num_rows(),
+            "num_mutations": self.mutations().num_rows(),
+            "breakpoints": self.breakpoints(),
+            "trees": trees,
+        });
+        serde_json::to_string(&summary).map_err(|e| TskitError::ValueError {
+            got: e.to_string(),
+            expected: String::from("JSON-serializable summary"),
+        })
+    }
+
+    /// Return an [`Iterator`] over summaries of each tree.
+    ///
+    /// Unlike [`TreeSequence::tree_iterator`], this works with
+    /// plain `for` loops and iterator adaptors.
+    /// For full access to each [`Tree`], use
+    /// [`TreeSequence::tree_iterator`].
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if the underlying [`Tree`] cannot be created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// tables.build_index();
+    /// let tree_sequence = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// for tree in tree_sequence.trees(tskit::TreeFlags::default()).unwrap() {
+    ///     assert_eq!(tree.interval, (0., 1000.));
+    /// }
+    /// ```
+    pub fn trees(&self, flags: TreeFlags) -> Result<TreesIterator<'_>, TskitError> {
+        Ok(TreesIterator {
+            tree: self.tree_iterator(flags)?,
+            treeseq: std::marker::PhantomData,
+        })
+    }
+
+    /// Return an [`Iterator`] over copies of each tree.
+    ///
+    /// Each [`TreeSnapshot`] owns a copy of the parent array,
+    /// so the memory cost is `O(num_nodes)` per tree.
+    /// In exchange, the snapshots may be stored, filtered, or
+    /// collected freely.
+    /// See [`TreeSequence::trees`] for a cheaper alternative.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if the underlying [`Tree`] cannot be created.
+    pub fn tree_snapshots(
+        &self,
+        flags: TreeFlags,
+    ) -> Result<impl Iterator<Item = TreeSnapshot> + '_, TskitError> {
+        use streaming_iterator::StreamingIterator;
+        let mut tree = self.tree_iterator(flags)?;
+        Ok(std::iter::from_fn(move || {
+            tree.next().map(TreeSnapshot::new)
+        }))
+    }
+
+    /// Get the list of samples as a vector.
+    #[deprecated(
+        since = "0.2.3",
+        note = "Please use TreeSequence::sample_nodes instead"
+    )]
+    pub fn samples_to_vec(&self) -> Vec<tsk_id_t> {
+        let num_samples = unsafe { ll_bindings::tsk_treeseq_get_num_samples(self.as_ptr()) };
+        let mut rv = vec![];
+
+        for i in 0..num_samples {
+            let u = unsafe { *(*self.as_ptr()).samples.offset(i as isize) };
+            rv.push(u);
+        }
+        rv
+    }
+
+    /// Get the list of sample nodes as a slice.
+    pub fn sample_nodes(&self) -> &[tsk_id_t] {
+        let num_samples = unsafe { ll_bindings::tsk_treeseq_get_num_samples(self.as_ptr()) };
+        tree_array_slice!(self, samples, num_samples)
+    }
+
+    /// Return `true` if [`TreeSequence::sample_nodes`] contains
+    /// exactly the nodes whose flags contain
+    /// [`crate::TSK_NODE_IS_SAMPLE`].
+    ///
+    /// The samples of a tree sequence are found when it is
+    /// created, so editing the node flags afterwards
+    /// (e.g., via [`NodeTable::flags_array_mut`])
+    /// leads to a mismatch.
+    pub fn sample_nodes_match_flags(&self) -> bool {
+        let flags = self.nodes().flags_array();
+        let samples = self.sample_nodes();
+        let is_sample = |u: usize| flags[u] & crate::TSK_NODE_IS_SAMPLE > 0;
+        // The sample nodes are unique, so counting suffices.
+        (0..flags.len()).filter(|&u| is_sample(u)).count() == samples.len()
+            && samples.iter().all(|&u| is_sample(u as usize))
+    }
+
+    /// Get the sample nodes belonging to population `pop`.
+    ///
+    /// The samples are returned in the same order as in
+    /// [`TreeSequence::sample_nodes`].
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `pop` is not a valid population id.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1.).unwrap();
+    /// tables.add_population().unwrap();
+    /// tables.add_population().unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, 1, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, 0, tskit::TSK_NULL).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// assert_eq!(treeseq.samples_in_population(1).unwrap(), vec![0]);
+    /// ```
+    pub fn samples_in_population(&self, pop: tsk_id_t) -> Result<Vec<tsk_id_t>, TskitError> {
+        if pop < 0 || pop as tsk_size_t >= self.populations().num_rows() {
+            return Err(TskitError::IndexError);
+        }
+        let population = self.nodes().population_array();
+        Ok(self
+            .sample_nodes()
+            .iter()
+            .filter(|&&u| population[u as usize] == pop)
+            .cloned()
+            .collect())
+    }
+
+    /// Get the sample nodes of each population.
+    ///
+    /// The value at index `i` contains the samples of population `i`,
+    /// in the same order as in [`TreeSequence::sample_nodes`].
+    /// Populations without samples have empty entries.
+    /// Samples whose population is [`TSK_NULL`] are not included.
+    ///
+    /// See [`TreeSequence::sample_sets_by_population_into`] to
+    /// avoid allocating a vector for each population.
+    pub fn sample_sets_by_population(&self) -> Vec<Vec<tsk_id_t>> {
+        let mut buffer = vec![];
+        self.sample_sets_by_population_into(&mut buffer)
+            .iter()
+            .map(|s| s.to_vec())
+            .collect()
+    }
+
+    /// Get the sample nodes of each population as slices of `buffer`.
+    ///
+    /// The contents of `buffer` are replaced by the samples,
+    /// grouped by population id.
+    /// The return value is ordered as for
+    /// [`TreeSequence::sample_sets_by_population`] and can be passed
+    /// directly to statistics such as [`TreeSequence::diversity`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1.).unwrap();
+    /// tables.add_population().unwrap();
+    /// tables.add_population().unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, 1, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, 0, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, 1, tskit::TSK_NULL).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let mut buffer = vec![];
+    /// let sample_sets = treeseq.sample_sets_by_population_into(&mut buffer);
+    /// assert_eq!(sample_sets, vec![&[1][..], &[0, 2][..]]);
+    /// ```
+    pub fn sample_sets_by_population_into<'b>(
+        &self,
+        buffer: &'b mut Vec<tsk_id_t>,
+    ) -> Vec<&'b [tsk_id_t]> {
+        let num_populations = self.populations().num_rows() as usize;
+        let population = self.nodes().population_array();
+        let samples = self.sample_nodes();
+        let mut offsets = vec![0; num_populations + 1];
+        for &u in samples {
+            let p = population[u as usize];
+            if p != TSK_NULL {
+                offsets[p as usize + 1] += 1;
+            }
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+        buffer.clear();
+        buffer.resize(offsets[num_populations], TSK_NULL);
+        let mut next = offsets.clone();
+        for &u in samples {
+            let p = population[u as usize];
+            if p != TSK_NULL {
+                buffer[next[p as usize]] = u;
+                next[p as usize] += 1;
+            }
+        }
+        let buffer: &'b [tsk_id_t] = buffer;
+        offsets.windows(2).map(|w| &buffer[w[0]..w[1]]).collect()
+    }
+
+    /// Get the nodes belonging to individual `ind`, in table order.
+    ///
+    /// The node table is scanned, so the result includes nodes
+    /// that are not samples.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `ind` is not a valid individual id.
+    pub fn nodes_for_individual(&self, ind: tsk_id_t) -> Result<Vec<tsk_id_t>, TskitError> {
+        if ind < 0 || ind as tsk_size_t >= self.individuals().num_rows() {
+            return Err(TskitError::IndexError);
+        }
+        Ok(self
+            .nodes()
+            .individual_array()
+            .iter()
+            .enumerate()
+            .filter(|(_, &i)| i == ind)
+            .map(|(u, _)| u as tsk_id_t)
+            .collect())
+    }
+
+    /// Get the ids of the mutations at site `site`, in table order.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `site` is not a valid site id.
+    pub fn mutations_at_site(&self, site: tsk_id_t) -> Result<Vec<tsk_id_t>, TskitError> {
+        if site < 0 || site as tsk_size_t >= self.sites().num_rows() {
+            return Err(TskitError::IndexError);
+        }
+        let mutations = self.mutations();
+        let mut rv = vec![];
+        for m in 0..mutations.num_rows() as tsk_id_t {
+            if mutations.site(m)? == site {
+                rv.push(m);
+            }
+        }
+        Ok(rv)
+    }
+
+    /// Get the allele carried by node `sample` at site `site`.
+    ///
+    /// The allele is the derived state of the closest mutation
+    /// at `site` on the path from `sample` to the root of the tree
+    /// containing the site.  If there is no such mutation, the
+    /// site's ancestral state is returned.
+    /// When a node carries more than one mutation at the site,
+    /// the last one in table order is used.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if `site` or `sample` are out of range.
+    /// * [`TskitError`] if the underlying [`Tree`] cannot be created.
+    pub fn allele_state(&self, site: tsk_id_t, sample: tsk_id_t) -> Result<Vec<u8>, TskitError> {
+        let mutation_ids = self.mutations_at_site(site)?;
+        if sample < 0 || sample as tsk_size_t >= self.nodes().num_rows() {
+            return Err(TskitError::IndexError);
+        }
+        let sites = self.sites();
+        let mutations = self.mutations();
+        let mut tree = self.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS)?;
+        tree.seek(sites.position(site)?)?;
+        let mut u = sample;
+        while u != TSK_NULL {
+            for &m in mutation_ids.iter().rev() {
+                if mutations.node(m)? == u {
+                    return Ok(mutations.derived_state(m)?.unwrap_or_default());
                 }
+            }
+            u = tree.parent(u)?;
+        }
+        Ok(sites.ancestral_state(site)?.unwrap_or_default())
+    }
+
+    /// Get the breakpoints between trees as a slice.
+    ///
+    /// The slice has length `num_trees + 1`.  The first
+    /// element is `0` and the last is the sequence length.
+    /// Tree `i` covers `[breakpoints[i], breakpoints[i + 1])`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// assert_eq!(treeseq.breakpoints(), &[0., 1000.]);
+    /// ```
+    pub fn breakpoints(&self) -> &[f64] {
+        unsafe {
+            std::slice::from_raw_parts(
+                ll_bindings::tsk_treeseq_get_breakpoints(self.as_ptr()),
+                self.num_trees() as usize + 1,
             )
-        )
+        }
     }
-}
 
-impl TableAccess for TreeSequence {
-    fn edges(&self) -> EdgeTable {
-        EdgeTable::new_from_table(unsafe { &(*self.inner.tables).edges })
+    /// Get the number of trees whose intervals overlap `[left, right)`.
+    ///
+    /// The trees are counted using the breakpoints between
+    /// trees, without creating a [`Tree`].
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`] unless `0 <= left < right <= sequence_length`.
+    pub fn num_trees_in_interval(&self, left: f64, right: f64) -> Result<tsk_size_t, TskitError> {
+        self.validate_interval(left, right)?;
+        let breakpoints = self.breakpoints();
+        let first = breakpoints.partition_point(|&x| x <= left);
+        let last = breakpoints.partition_point(|&x| x < right);
+        Ok((last - first + 1) as tsk_size_t)
     }
 
-    fn individuals(&self) -> IndividualTable {
-        IndividualTable::new_from_table(unsafe { &(*self.inner.tables).individuals })
+    fn validate_interval(&self, left: f64, right: f64) -> Result<(), TskitError> {
+        let sequence_length = unsafe { (*self.inner.tables).sequence_length };
+        if !(left.is_finite() && right.is_finite() && 0.0 <= left && left < right)
+            || right > sequence_length
+        {
+            return Err(TskitError::ValueError {
+                got: format!("[{}, {})", left, right),
+                expected: format!("0 <= left < right <= {}", sequence_length),
+            });
+        }
+        Ok(())
     }
 
-    fn migrations(&self) -> MigrationTable {
-        MigrationTable::new_from_table(unsafe { &(*self.inner.tables).migrations })
+    /// Build a [`SiteMutationIndex`](crate::SiteMutationIndex),
+    /// giving the mutations at each site without scanning
+    /// the mutation table.
+    ///
+    /// For a single site, see [`TreeSequence::mutations_at_site`].
+    pub fn build_site_mutation_index(&self) -> crate::SiteMutationIndex<'_> {
+        let mutations = unsafe { &(*self.inner.tables).mutations };
+        let sites = if mutations.num_rows == 0 {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(mutations.site, mutations.num_rows as usize) }
+        };
+        crate::SiteMutationIndex::new(self.sites().num_rows() as usize, sites)
+    }
+
+    /// Get the number of trees.
+    pub fn num_trees(&self) -> tsk_size_t {
+        unsafe { ll_bindings::tsk_treeseq_get_num_trees(self.as_ptr()) }
+    }
+
+    /// Calculate the average Kendall-Colijn (`K-C`) distance between
+    /// pairs of trees whose intervals overlap.
+    ///
+    /// # Note
+    ///
+    /// * [Citation](https://doi.org/10.1093/molbev/msw124)
+    ///
+    /// # Parameters
+    ///
+    /// * `lambda` specifies the relative weight of topology and branch length.
+    ///    See [`Tree::kc_distance`] for more details.
+    pub fn kc_distance(&self, other: &TreeSequence, lambda: f64) -> Result<f64, TskitError> {
+        let mut kc: f64 = f64::NAN;
+        let kcp: *mut f64 = &mut kc;
+        let code = unsafe {
+            ll_bindings::tsk_treeseq_kc_distance(self.as_ptr(), other.as_ptr(), lambda, kcp)
+        };
+        handle_tsk_return_value!(code, kc)
+    }
+
+    // FIXME: document
+    pub fn num_samples(&self) -> tsk_size_t {
+        unsafe { ll_bindings::tsk_treeseq_get_num_samples(self.as_ptr()) }
+    }
+
+    /// Return the minimum and maximum times of the sample nodes.
+    ///
+    /// Only sample nodes are considered, so the range may be
+    /// narrower than that of the entire node table.
+    ///
+    /// Returns `None` if there are no samples.
+    pub fn sample_time_range(&self) -> Option<(f64, f64)> {
+        let nodes = self.nodes();
+        self.sample_nodes().iter().fold(None, |range, &s| {
+            let t = nodes.time(s).ok()?;
+            match range {
+                None => Some((t, t)),
+                Some((min, max)) => Some((f64::min(min, t), f64::max(max, t))),
+            }
+        })
+    }
+
+    /// Obtain the genotypes of all samples at all sites.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] will be raised if the underlying C library returns an error code.
+    pub fn genotype_matrix(&self) -> Result<crate::GenotypeMatrix, TskitError> {
+        crate::genotypes::genotype_matrix(self, None)
+    }
+
+    /// Obtain the genotypes of a subset of samples at all sites.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples`: the sample nodes to include.  The columns of the
+    ///   return value are in the same order as this slice.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if a node is out of range.
+    /// * [`TskitError::ValueError`] if a node is not a sample.
+    /// * [`TskitError::ErrorCode`] if `samples` contains duplicates.
+    pub fn genotype_matrix_for_samples(
+        &self,
+        samples: &[tsk_id_t],
+    ) -> Result<crate::GenotypeMatrix, TskitError> {
+        let nodes = self.nodes();
+        for s in samples {
+            if !nodes.is_sample(*s)? {
+                return Err(TskitError::ValueError {
+                    got: format!("non-sample node {}", s),
+                    expected: String::from("sample node"),
+                });
+            }
+        }
+        crate::genotypes::genotype_matrix(self, Some(samples))
+    }
+
+    /// Return an [`LdCalculator`](crate::LdCalculator) for this tree sequence.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ErrorCode`] if the C library returns an error.
+    pub fn ld_calculator(&self) -> Result<crate::LdCalculator<'_>, TskitError> {
+        crate::LdCalculator::new(self)
+    }
+
+    /// Calculate nucleotide diversity.
+    ///
+    /// The result is normalised by the span of each window.
+    /// For other options, see [`TreeSequence::diversity_with_options`].
+    ///
+    /// # Parameters
+    ///
+    /// * `sample_sets`: the sets of samples for which to calculate
+    ///   diversity.
+    /// * `windows`: the breakpoints of the genomic windows.  The first
+    ///   value must be `0.0` and the last must be the sequence length.
+    ///   If `None`, a single window spanning the genome is used.
+    /// * `mode`: a [`crate::StatisticsMode`].
+    ///
+    /// # Returns
+    ///
+    /// The values for each window, in order.  Within each window,
+    /// there is one value per sample set.  For
+    /// [`crate::StatisticsMode::Node`], there is one value per
+    /// sample set for each node, ordered by node.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `windows` has fewer than two values.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    pub fn diversity(
+        &self,
+        sample_sets: &[&[tsk_id_t]],
+        windows: Option<&[f64]>,
+        mode: crate::StatisticsMode,
+    ) -> Result<Vec<f64>, TskitError> {
+        self.diversity_with_options(
+            sample_sets,
+            windows,
+            crate::StatisticsOptions::from(mode) | crate::StatisticsOptions::SPAN_NORMALISE,
+        )
+    }
+
+    /// Calculate nucleotide diversity with full control over
+    /// the statistic's options.
+    ///
+    /// See [`TreeSequence::diversity`] for details of the
+    /// other parameters and of the return value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::StatisticsOptions as SO;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 1.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::TSK_NULL, tskit::TSK_NULL).unwrap();
+    /// tables.add_edge(0., 100., 0, 1).unwrap();
+    /// tables.add_edge(0., 100., 0, 2).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let samples = treeseq.sample_nodes();
+    /// let d = treeseq.diversity_with_options(&[samples], None, SO::BRANCH).unwrap();
+    /// // The two samples are separated by two branches of length 1
+    /// // on a tree that spans 100 units of genome.
+    /// assert_eq!(d, vec![200.]);
+    /// ```
+    pub fn diversity_with_options(
+        &self,
+        sample_sets: &[&[tsk_id_t]],
+        windows: Option<&[f64]>,
+        options: crate::StatisticsOptions,
+    ) -> Result<Vec<f64>, TskitError> {
+        self.run_windowed_stat(
+            ll_bindings::tsk_treeseq_diversity,
+            sample_sets,
+            windows,
+            options,
+        )
+    }
+
+    /// Calculate the density of segregating sites.
+    ///
+    /// The result is normalised by the span of each window.
+    /// See [`TreeSequence::diversity`] for details of the
+    /// parameters and of the return value.
+    pub fn segregating_sites(
+        &self,
+        sample_sets: &[&[tsk_id_t]],
+        windows: Option<&[f64]>,
+        mode: crate::StatisticsMode,
+    ) -> Result<Vec<f64>, TskitError> {
+        self.run_windowed_stat(
+            ll_bindings::tsk_treeseq_segregating_sites,
+            sample_sets,
+            windows,
+            crate::StatisticsOptions::from(mode) | crate::StatisticsOptions::SPAN_NORMALISE,
+        )
+    }
+
+    /// Calculate Tajima's D.
+    ///
+    /// The statistic is calculated from site-based diversity
+    /// and the number of segregating sites in each window.
+    ///
+    /// # Parameters
+    ///
+    /// * `sample_sets`: the sets of samples for which to calculate
+    ///   the statistic.
+    /// * `windows`: the breakpoints of the genomic windows.
+    ///   See [`TreeSequence::diversity`].
+    ///
+    /// # Returns
+    ///
+    /// The values for each window, in order.  Within each window,
+    /// there is one value per sample set.
+    /// The value is `NaN` for sample sets with fewer than two samples
+    /// and for windows without segregating sites.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `windows` has fewer than two values.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    #[allow(non_snake_case)]
+    pub fn Tajimas_D(
+        &self,
+        sample_sets: &[&[tsk_id_t]],
+        windows: Option<&[f64]>,
+    ) -> Result<Vec<f64>, TskitError> {
+        let pi = self.run_windowed_stat(
+            ll_bindings::tsk_treeseq_diversity,
+            sample_sets,
+            windows,
+            crate::StatisticsOptions::SITE,
+        )?;
+        let segsites = self.run_windowed_stat(
+            ll_bindings::tsk_treeseq_segregating_sites,
+            sample_sets,
+            windows,
+            crate::StatisticsOptions::SITE,
+        )?;
+        Ok(pi
+            .iter()
+            .zip(segsites.iter())
+            .enumerate()
+            .map(|(i, (p, s))| {
+                crate::stats::tajimas_d(sample_sets[i % sample_sets.len()].len(), *p, *s)
+            })
+            .collect())
+    }
+
+    /// Simplify tables and return a new tree sequence.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples`: a slice containing non-null node ids.
+    ///   The tables are simplified with respect to the ancestry
+    ///   of these nodes.
+    /// * `options`: A [`SimplificationOptions`] bit field controlling
+    ///   the behavior of simplification.
+    ///
+    /// To also obtain the mapping of input to output nodes,
+    /// see [`TreeSequence::simplify_keep_idmap`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `samples` contains [`TSK_NULL`]
+    ///   or ids out of range.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    pub fn simplify(
+        &self,
+        samples: &[tsk_id_t],
+        options: SimplificationOptions,
+    ) -> Result<Self, TskitError> {
+        let (ts, _) = self.simplify_details(samples, options, false)?;
+        Ok(ts)
+    }
+
+    /// Simplify tables and return a new tree sequence along
+    /// with the mapping of input to output nodes.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples`: a slice containing non-null node ids.
+    ///   The tables are simplified with respect to the ancestry
+    ///   of these nodes.
+    /// * `options`: A [`SimplificationOptions`] bit field controlling
+    ///   the behavior of simplification.
+    ///
+    /// # Returns
+    ///
+    /// The new tree sequence and a vector equal in length to the
+    /// input node table.  For each input node, this vector either
+    /// contains the node's new index or [`TSK_NULL`] if the input
+    /// node is not part of the simplified history.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if `samples` contains [`TSK_NULL`]
+    ///   or ids out of range.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    pub fn simplify_keep_idmap(
+        &self,
+        samples: &[tsk_id_t],
+        options: SimplificationOptions,
+    ) -> Result<(Self, Vec<tsk_id_t>), TskitError> {
+        let (ts, idmap) = self.simplify_details(samples, options, true)?;
+        Ok((ts, idmap.unwrap()))
+    }
+
+    /// Return a new tree sequence containing only the genomic
+    /// information in the half-open interval `[left, right)`.
+    ///
+    /// Edges and migrations are clipped to the interval.
+    /// Sites outside of the interval are removed, along with
+    /// their mutations.
+    /// The sequence length of the result is unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `left`: the left end of the interval to keep.
+    /// * `right`: the right end of the interval to keep.
+    /// * `simplify`: if `true`, the result is simplified with respect
+    ///   to the current samples using [`SimplificationOptions::default`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] unless `0 <= left < right <= sequence_length`.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    pub fn keep_interval(
+        &self,
+        left: f64,
+        right: f64,
+        simplify: bool,
+    ) -> Result<TreeSequence, TskitError> {
+        self.validate_interval(left, right)?;
+        let mut tables = self.dump_tables()?;
+        tables.keep_interval(left, right)?;
+        if simplify {
+            tables.simplify(self.sample_nodes(), SimplificationOptions::default(), false)?;
+        }
+        tables.build_index()?;
+        tables.tree_sequence(TreeSequenceFlags::default())
+    }
+
+    /// Split the tree sequence into the genomic intervals
+    /// `[0, position)` and `[position, sequence_length)`.
+    ///
+    /// Edges and migrations are clipped to each interval.
+    /// Sites are kept in the half containing their position,
+    /// along with their mutations.
+    /// Node, individual, and population tables are copied
+    /// into both halves without simplification.
+    ///
+    /// The sequence length of the left half is `position`.
+    /// The coordinates of the right half are shifted
+    /// to start at 0, so its sequence length is
+    /// `sequence_length - position` and a site at `position + x`
+    /// in `self` is at `x` in the right half.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] unless `0 < position < sequence_length`.
+    /// * [`TskitError::ErrorCode`] if the C library returns an error.
+    pub fn split_at(&self, position: f64) -> Result<(TreeSequence, TreeSequence), TskitError> {
+        let sequence_length = unsafe { (*self.inner.tables).sequence_length };
+        if !(position > 0.0 && position < sequence_length) {
+            return Err(TskitError::ValueError {
+                got: position.to_string(),
+                expected: format!("0 < position < {}", sequence_length),
+            });
+        }
+        let mut halves = vec![];
+        for (left, right) in [(0.0, position), (position, sequence_length)].iter() {
+            let mut tables = self.dump_tables()?;
+            tables.trim_to_interval(*left, *right)?;
+            tables.build_index()?;
+            halves.push(tables.tree_sequence(TreeSequenceFlags::default())?);
+        }
+        let right = halves.pop().unwrap();
+        let left = halves.pop().unwrap();
+        Ok((left, right))
+    }
+
+    fn simplify_details(
+        &self,
+        samples: &[tsk_id_t],
+        options: SimplificationOptions,
+        idmap: bool,
+    ) -> Result<(Self, Option<Vec<tsk_id_t>>), TskitError> {
+        crate::util::validate_sample_ids(samples, self.nodes().num_rows())?;
+        let mut tables = TableCollection::new(unsafe { (*self.inner.tables).sequence_length })?;
+        tables.build_index().unwrap();
+        let mut ts = tables.tree_sequence(TreeSequenceFlags::default())?;
+        let mut output_node_map: Vec<tsk_id_t> = vec![];
+        if idmap {
+            output_node_map.resize(self.nodes().num_rows() as usize, TSK_NULL);
+        }
+        let rv = unsafe {
+            ll_bindings::tsk_treeseq_simplify(
+                self.as_ptr(),
+                samples.as_ptr(),
+                samples.len() as tsk_size_t,
+                options.bits(),
+                ts.as_mut_ptr(),
+                match idmap {
+                    true => output_node_map.as_mut_ptr(),
+                    false => std::ptr::null_mut(),
+                },
+            )
+        };
+        handle_tsk_return_value!(
+            rv,
+            (
+                ts,
+                match idmap {
+                    true => Some(output_node_map),
+                    false => None,
+                }
+            )
+        )
+    }
+}
+
+/// Create a [`TreeSequence`] from a [`TableCollection`].
+///
+/// Equivalent to [`TreeSequence::new`] with default
+/// [`TreeSequenceFlags`], so the tables must be indexed.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryInto;
+/// let mut tables = tskit::TableCollection::new(100.).unwrap();
+/// tables.build_index().unwrap();
+/// let treeseq: tskit::TreeSequence = tables.try_into().unwrap();
+/// assert_eq!(treeseq.num_trees(), 1);
+/// ```
+impl std::convert::TryFrom<TableCollection> for TreeSequence {
+    type Error = TskitError;
+
+    fn try_from(tables: TableCollection) -> Result<Self, Self::Error> {
+        Self::new(tables, TreeSequenceFlags::default())
+    }
+}
+
+impl TableAccess for TreeSequence {
+    fn edges(&self) -> EdgeTable {
+        EdgeTable::new_from_table(unsafe { &(*self.inner.tables).edges })
+    }
+
+    fn individuals(&self) -> IndividualTable {
+        IndividualTable::new_from_table(unsafe { &(*self.inner.tables).individuals })
+    }
+
+    fn migrations(&self) -> MigrationTable {
+        MigrationTable::new_from_table(unsafe { &(*self.inner.tables).migrations })
+    }
+
+    fn nodes(&self) -> NodeTable {
+        NodeTable::new_from_table(unsafe { &(*self.inner.tables).nodes })
+    }
+
+    fn sites(&self) -> SiteTable {
+        SiteTable::new_from_table(unsafe { &(*self.inner.tables).sites })
+    }
+
+    fn mutations(&self) -> MutationTable {
+        MutationTable::new_from_table(unsafe { &(*self.inner.tables).mutations })
+    }
+
+    fn populations(&self) -> PopulationTable {
+        PopulationTable::new_from_table(unsafe { &(*self.inner.tables).populations })
+    }
+}
+
+impl crate::traits::NodeListGenerator for TreeSequence {}
+
+#[cfg(any(doc, feature = "provenance"))]
+impl crate::provenance::Provenance for TreeSequence {
+    fn add_provenance_with_timestamp(&mut self, record: &str, timestamp: &str) -> TskReturnValue {
+        crate::provenance::validate_provenance(record, timestamp)?;
+        let rv = unsafe {
+            ll_bindings::tsk_provenance_table_add_row(
+                &mut (*self.inner.tables).provenances,
+                timestamp.as_ptr() as *mut i8,
+                timestamp.len() as tsk_size_t,
+                record.as_ptr() as *mut i8,
+                record.len() as tsk_size_t,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    fn provenances(&self) -> crate::provenance::ProvenanceTable {
+        crate::provenance::ProvenanceTable::new_from_table(unsafe {
+            &(*self.inner.tables).provenances
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_trees {
+    use super::*;
+    use crate::test_fixtures::{
+        make_small_table_collection, make_small_table_collection_two_trees,
+        treeseq_from_small_table_collection, treeseq_from_small_table_collection_two_trees,
+    };
+    use streaming_iterator::DoubleEndedStreamingIterator;
+    use streaming_iterator::StreamingIterator;
+
+    #[test]
+    fn test_create_treeseq_new_from_tables() {
+        let tables = make_small_table_collection();
+        let treeseq = TreeSequence::new(tables, TreeSequenceFlags::default()).unwrap();
+        let samples = treeseq.sample_nodes();
+        assert_eq!(samples.len(), 2);
+        for i in 1..3 {
+            assert_eq!(samples[i - 1], i as tsk_id_t);
+        }
+    }
+
+    #[test]
+    fn test_create_treeseq_from_tables() {
+        let tables = make_small_table_collection();
+        let _treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    }
+
+    #[test]
+    fn test_iterate_tree_seq_with_one_tree() {
+        let tables = make_small_table_collection();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let mut ntrees = 0;
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            ntrees += 1;
+            assert_eq!(tree.current_tree, ntrees);
+            let samples = tree.sample_nodes();
+            assert_eq!(samples.len(), 2);
+            for i in 1..3 {
+                assert_eq!(samples[i - 1], i as tsk_id_t);
+
+                let mut nsteps = 0;
+                for _ in tree.parents(samples[i - 1]).unwrap() {
+                    nsteps += 1;
+                }
+                assert_eq!(nsteps, 2);
+            }
+            let roots = tree.roots_to_vec();
+            for r in roots.iter() {
+                let mut num_children = 0;
+                for _ in tree.children(*r).unwrap() {
+                    num_children += 1;
+                }
+                assert_eq!(num_children, 2);
+            }
+        }
+        assert_eq!(ntrees, 1);
+    }
+
+    #[test]
+    fn test_iterate_no_roots() {
+        let mut tables = TableCollection::new(100.).unwrap();
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            let mut num_roots = 0;
+            for _ in tree.roots() {
+                num_roots += 1;
+            }
+            assert_eq!(num_roots, 0);
+        }
+    }
+
+    #[should_panic]
+    #[test]
+    fn test_samples_iterator_error_when_not_tracking_samples() {
+        let tables = make_small_table_collection();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        if let Some(tree) = tree_iter.next() {
+            for n in tree.traverse_nodes(NodeTraversalOrder::Preorder) {
+                for _ in tree.samples(n).unwrap() {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_num_tracked_samples() {
+        let treeseq = treeseq_from_small_table_collection();
+        assert_eq!(treeseq.inner.num_samples, 2);
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        if let Some(tree) = tree_iter.next() {
+            assert_eq!(tree.num_tracked_samples(2).unwrap(), 1);
+            assert_eq!(tree.num_tracked_samples(1).unwrap(), 1);
+            assert_eq!(tree.num_tracked_samples(0).unwrap(), 2);
+        }
+    }
+
+    #[test]
+    fn test_incompatible_tree_flags() {
+        let treeseq = treeseq_from_small_table_collection();
+        assert!(matches!(
+            treeseq.tree_iterator(TreeFlags::SAMPLE_LISTS | TreeFlags::NO_SAMPLE_COUNTS),
+            Err(TskitError::ValueError {
+                got: _,
+                expected: _
+            })
+        ));
+        assert!(treeseq.tree_iterator(TreeFlags::SAMPLE_LISTS).is_ok());
+        assert!(treeseq.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS).is_ok());
+    }
+
+    #[test]
+    fn test_sample_traversal() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let nodes = treeseq.nodes();
+        while let Some(tree) = tree_iter.next() {
+            let samples = tree
+                .sample_traversal(NodeTraversalOrder::Preorder)
+                .collect::<Vec<_>>();
+            let preorder = tree
+                .traverse_nodes(NodeTraversalOrder::Preorder)
+                .filter(|&u| nodes.is_sample(u).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(samples, preorder);
+            let mut sorted = samples.clone();
+            sorted.sort_unstable();
+            let mut expected = tree.sample_nodes().to_vec();
+            expected.sort_unstable();
+            assert_eq!(sorted, expected);
+        }
+    }
+
+    #[test]
+    fn test_sample_mrca_matrix() {
+        let treeseq = treeseq_from_small_table_collection();
+        let tree = treeseq.first_tree(TreeFlags::default()).unwrap();
+        assert_eq!(tree.sample_nodes(), &[1, 2]);
+        assert_eq!(tree.sample_mrca_matrix().unwrap(), vec![1, 0, 0, 2]);
+
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let mut ntrees = 0;
+        while let Some(tree) = tree_iter.next() {
+            let samples = tree.sample_nodes();
+            let n = samples.len();
+            let matrix = tree.sample_mrca_matrix().unwrap();
+            assert_eq!(matrix.len(), n * n);
+            let ancestors = |mut u: tsk_id_t| {
+                let mut rv = vec![];
+                while u != TSK_NULL {
+                    rv.push(u);
+                    u = tree.parent(u).unwrap();
+                }
+                rv
+            };
+            for i in 0..n {
+                let a = ancestors(samples[i]);
+                for j in 0..n {
+                    let b = ancestors(samples[j]);
+                    let expected = *a.iter().find(|u| b.contains(u)).unwrap_or(&TSK_NULL);
+                    assert_eq!(matrix[i * n + j], expected);
+                }
+            }
+            ntrees += 1;
+        }
+        assert_eq!(ntrees, 2);
+    }
+
+    #[test]
+    fn test_write_newick() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_node(0, 2.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        for _ in 0..3 {
+            tables
+                .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, TSK_NULL, TSK_NULL)
+                .unwrap();
+        }
+        tables.add_edge(0., 1000., 0, 1).unwrap();
+        tables.add_edge(0., 500., 0, 2).unwrap();
+        tables.add_edge(500., 1000., 0, 4).unwrap();
+        tables.add_edge(0., 500., 1, 3).unwrap();
+        tables.add_edge(0., 500., 1, 4).unwrap();
+        tables.add_edge(500., 1000., 1, 2).unwrap();
+        tables.add_edge(500., 1000., 1, 3).unwrap();
+        tables
+            .full_sort(crate::TableSortOptions::default())
+            .unwrap();
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+        let mut buffer = vec![];
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            tree.write_newick(&mut buffer, 2).unwrap();
+        }
+        let text = String::from_utf8(buffer).unwrap();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        for line in lines.iter() {
+            assert!(line.starts_with('('));
+            assert!(line.ends_with(';'));
+            assert_eq!(line.matches('(').count(), line.matches(')').count());
+            for label in ["3:", "4:", "5:"].iter() {
+                assert!(line.contains(label));
+            }
+        }
+        assert_eq!(lines[0], "((4:1.00,5:1.00):1.00,3:2.00);");
+
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let tree = treeseq.first_tree(TreeFlags::default()).unwrap();
+        assert!(tree.write_newick(&mut vec![], 2).is_err());
+        let tree = treeseq.last_tree(TreeFlags::default()).unwrap();
+        assert!(tree.write_newick(&mut vec![], -1).is_err());
+    }
+
+    #[test]
+    fn test_diversity_contribution() {
+        let treeseq = treeseq_from_small_table_collection();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        if let Some(tree) = tree_iter.next() {
+            // Two branches of length 1, each above half of the samples.
+            assert!((tree.diversity_contribution().unwrap() - 0.5).abs() <= 1e-12);
+        }
+
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS).unwrap();
+        if let Some(tree) = tree_iter.next() {
+            assert!(matches!(
+                tree.diversity_contribution(),
+                Err(TskitError::NotTrackingSamples)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_num_tracked_samples_not_tracking_samples() {
+        let treeseq = treeseq_from_small_table_collection();
+        assert_eq!(treeseq.inner.num_samples, 2);
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS).unwrap();
+        if let Some(tree) = tree_iter.next() {
+            for u in 0..3 {
+                assert!(matches!(
+                    tree.num_tracked_samples(u),
+                    Err(TskitError::NotTrackingSamples)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_tracked_samples() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        tree_iter.set_tracked_samples(&[4]).unwrap();
+        let mut expected_root_counts = vec![0, 1].into_iter();
+        while let Some(tree) = tree_iter.next() {
+            assert_eq!(tree.num_tracked_samples(1).unwrap(), 1);
+            assert_eq!(tree.num_tracked_samples(4).unwrap(), 1);
+            assert_eq!(tree.num_tracked_samples(5).unwrap(), 0);
+            assert_eq!(
+                tree.num_tracked_samples(0).unwrap(),
+                expected_root_counts.next().unwrap()
+            );
+        }
+
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        assert!(tree_iter.set_tracked_samples(&[0]).is_err());
+        assert!(tree_iter.set_tracked_samples(&[TSK_NULL]).is_err());
+        assert!(tree_iter.set_tracked_samples(&[6]).is_err());
+        assert!(tree_iter.set_tracked_samples(&[2, 2]).is_err());
+
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS).unwrap();
+        assert!(matches!(
+            tree_iter.set_tracked_samples(&[2]),
+            Err(TskitError::NotTrackingSamples)
+        ));
+    }
+
+    #[test]
+    fn test_iterate_samples() {
+        let tables = make_small_table_collection();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::SAMPLE_LISTS).unwrap();
+        if let Some(tree) = tree_iter.next() {
+            assert!(!tree.flags.contains(TreeFlags::NO_SAMPLE_COUNTS));
+            assert!(tree.flags.contains(TreeFlags::SAMPLE_LISTS));
+            let mut s = vec![];
+            for i in tree.samples(0).unwrap() {
+                s.push(i);
+            }
+            assert_eq!(s.len(), 2);
+            assert_eq!(s.len(), tree.num_tracked_samples(0).unwrap() as usize);
+            assert_eq!(s[0], 1);
+            assert_eq!(s[1], 2);
+
+            for u in 1..3 {
+                let mut s = vec![];
+                for i in tree.samples(u).unwrap() {
+                    s.push(i);
+                }
+                assert_eq!(s.len(), 1);
+                assert_eq!(s[0], u);
+                assert_eq!(s.len(), tree.num_tracked_samples(u).unwrap() as usize);
+            }
+        } else {
+            panic!("Expected a tree");
+        }
+    }
+
+    #[test]
+    fn test_iterate_samples_two_trees() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        assert_eq!(treeseq.inner.num_trees, 2);
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::SAMPLE_LISTS).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            for n in tree.traverse_nodes(NodeTraversalOrder::Preorder) {
+                let mut nsamples = 0;
+                for _ in tree.samples(n).unwrap() {
+                    nsamples += 1;
+                }
+                assert!(nsamples > 0);
+                assert_eq!(nsamples, tree.num_tracked_samples(n).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_kc_distance_naive_test() {
+        let ts1 = treeseq_from_small_table_collection();
+        let ts2 = treeseq_from_small_table_collection();
+
+        let kc = ts1.kc_distance(&ts2, 0.0).unwrap();
+        assert!(kc.is_finite());
+        assert!((kc - 0.).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_dump_tables() {
+        let tables = make_small_table_collection_two_trees();
+        // Have to make b/c tables will no longer exist after making the treeseq
+        let tables_copy = tables.deepcopy().unwrap();
+        let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let dumped = ts.dump_tables().unwrap();
+        assert!(tables_copy.equals(&dumped, crate::TableEqualityOptions::default()));
+    }
+
+    #[test]
+    fn test_table_collection_try_from_treeseq() {
+        use std::convert::TryFrom;
+        let ts = treeseq_from_small_table_collection_two_trees();
+        let tables = TableCollection::try_from(&ts).unwrap();
+        let dumped = ts.dump_tables().unwrap();
+        assert!(tables.equals(&dumped, crate::TableEqualityOptions::default()));
+    }
+
+    #[test]
+    fn test_treeseq_try_from_table_collection() {
+        use std::convert::TryInto;
+        let tables = make_small_table_collection_two_trees();
+        let copy = tables.deepcopy().unwrap();
+        let ts: TreeSequence = tables.try_into().unwrap();
+        assert_eq!(ts.num_trees(), 2);
+        assert!(copy.equals(
+            &ts.dump_tables().unwrap(),
+            crate::TableEqualityOptions::default()
+        ));
+
+        let tables = TableCollection::new(100.).unwrap();
+        let rv: Result<TreeSequence, TskitError> = tables.try_into();
+        assert!(matches!(rv, Err(TskitError::ErrorCode { code: _ })));
+    }
+
+    #[test]
+    fn test_reverse_tree_iteration() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let mut starts_fwd = vec![];
+        let mut stops_fwd = vec![];
+        let mut starts_rev = vec![];
+        let mut stops_rev = vec![];
+        while let Some(tree) = tree_iter.next() {
+            let interval = tree.interval();
+            starts_fwd.push(interval.0);
+            stops_fwd.push(interval.1);
+        }
+        assert_eq!(stops_fwd.len(), 2);
+        assert_eq!(stops_fwd.len(), 2);
+
+        // NOTE: we do NOT need to create a new iterator.
+        while let Some(tree) = tree_iter.next_back() {
+            let interval = tree.interval();
+            starts_rev.push(interval.0);
+            stops_rev.push(interval.1);
+        }
+        assert_eq!(starts_fwd.len(), starts_rev.len());
+        assert_eq!(stops_fwd.len(), stops_rev.len());
+
+        starts_rev.reverse();
+        assert!(starts_fwd == starts_rev);
+        stops_rev.reverse();
+        assert!(stops_fwd == stops_rev);
+    }
+
+    #[test]
+    fn test_seek_forward_then_backward() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+
+        assert_eq!(tree.seek_counting_steps(100.).unwrap(), 1);
+        assert_eq!(tree.interval(), (0., 500.));
+        assert_eq!(tree.seek_counting_steps(700.).unwrap(), 1);
+        assert_eq!(tree.interval(), (500., 1000.));
+        assert_eq!(tree.seek_counting_steps(600.).unwrap(), 0);
+        assert_eq!(tree.interval(), (500., 1000.));
+        assert_eq!(tree.seek_counting_steps(499.).unwrap(), 1);
+        assert_eq!(tree.interval(), (0., 500.));
+        assert_eq!(tree.seek_counting_steps(999.).unwrap(), 1);
+        assert_eq!(tree.interval(), (500., 1000.));
+
+        // Iteration continues from the current tree
+        tree.seek(0.).unwrap();
+        assert!(tree.next().is_some());
+        assert_eq!(tree.interval(), (500., 1000.));
+        assert!(tree.next().is_none());
+
+        assert!(tree.seek(1000.).is_err());
+        assert!(tree.seek(-1.).is_err());
+    }
+
+    #[test]
+    fn test_is_sample() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            for u in 0..treeseq.nodes().num_rows() as tsk_id_t {
+                assert_eq!(tree.is_sample(u).unwrap(), u > 1);
+            }
+            assert!(tree.is_sample(-1).is_err());
+            assert!(tree
+                .is_sample(treeseq.nodes().num_rows() as tsk_id_t)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_is_coalesced() {
+        let treeseq = treeseq_from_small_table_collection();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            assert_eq!(tree.num_roots(), 1);
+            assert!(tree.is_coalesced());
+        }
+
+        // The first tree has two roots.
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let mut num_roots = vec![];
+        while let Some(tree) = tree_iter.next() {
+            num_roots.push(tree.num_roots());
+            assert_eq!(tree.is_coalesced(), tree.num_roots() == 1);
+        }
+        assert_eq!(num_roots, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_parent_of_virtual_root() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            assert_eq!(tree.virtual_root(), treeseq.nodes().num_rows() as tsk_id_t);
+            assert_eq!(tree.parent(tree.virtual_root()).unwrap(), TSK_NULL);
+            assert!(tree.parent(tree.virtual_root() + 1).is_err());
+        }
+    }
+
+    #[test]
+    fn test_children_with_time() {
+        let treeseq = treeseq_from_small_table_collection();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            let nodes = treeseq.nodes();
+            for root in tree.roots() {
+                let children = tree.children(root).unwrap().collect::<Vec<_>>();
+                assert!(!children.is_empty());
+                let with_time = tree.children_with_time(root).unwrap().collect::<Vec<_>>();
+                assert_eq!(with_time.len(), children.len());
+                for ((c, t), expected) in with_time.iter().zip(children.iter()) {
+                    assert_eq!(c, expected);
+                    assert_eq!(*t, nodes.time(*c).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_collect_children_into() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let mut buf = vec![TSK_NULL; 10];
+        while let Some(tree) = tree_iter.next() {
+            for u in 0..treeseq.nodes().num_rows() as tsk_id_t {
+                tree.collect_children_into(u, &mut buf).unwrap();
+                assert_eq!(buf, tree.children(u).unwrap().collect::<Vec<_>>());
+            }
+            tree.collect_children_into(tree.virtual_root(), &mut buf)
+                .unwrap();
+            assert_eq!(buf, tree.roots_to_vec());
+            let before = buf.clone();
+            assert!(tree.collect_children_into(-2, &mut buf).is_err());
+            assert_eq!(buf, before);
+        }
+    }
+
+    #[test]
+    fn test_sample_time_range() {
+        let mut tables = TableCollection::new(100.).unwrap();
+        tables.add_node(0, 10.0, TSK_NULL, TSK_NULL).unwrap();
+        tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, TSK_NULL, TSK_NULL)
+            .unwrap();
+        tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 3.0, TSK_NULL, TSK_NULL)
+            .unwrap();
+        tables.add_edge(0., 100., 0, 1).unwrap();
+        tables.add_edge(0., 100., 0, 2).unwrap();
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert_eq!(treeseq.sample_time_range(), Some((0.0, 3.0)));
+
+        let tables = TableCollection::new(100.).unwrap();
+        let treeseq = tables
+            .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+            .unwrap();
+        assert!(treeseq.sample_time_range().is_none());
+    }
+
+    #[test]
+    fn test_keep_interval() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        for simplify in [false, true].iter() {
+            let trimmed = treeseq.keep_interval(250., 750., *simplify).unwrap();
+            assert_eq!(trimmed.dump_tables().unwrap().sequence_length(), 1000.);
+            let edges = trimmed.edges();
+            assert!(edges.num_rows() > 0);
+            for i in 0..edges.num_rows() as tsk_id_t {
+                assert!(edges.left(i).unwrap() >= 250.);
+                assert!(edges.right(i).unwrap() <= 750.);
+            }
+            assert_eq!(trimmed.num_trees(), 4);
+        }
+
+        for (left, right) in [
+            (-1., 500.),
+            (500., 500.),
+            (600., 500.),
+            (0., 1001.),
+            (0., f64::NAN),
+        ]
+        .iter()
+        {
+            assert!(matches!(
+                treeseq.keep_interval(*left, *right, false),
+                Err(TskitError::ValueError {
+                    got: _,
+                    expected: _
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_split_at() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(100., Some(b"0")).unwrap();
+        tables.add_site(700., Some(b"0")).unwrap();
+        tables
+            .add_mutation(0, 2, TSK_NULL, 0.5, Some(b"1"))
+            .unwrap();
+        tables
+            .add_mutation(1, 4, TSK_NULL, 0.5, Some(b"1"))
+            .unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let (left, right) = treeseq.split_at(250.).unwrap();
+        let left_length = left.dump_tables().unwrap().sequence_length();
+        let right_length = right.dump_tables().unwrap().sequence_length();
+        assert_eq!(left_length, 250.);
+        assert_eq!(right_length, 750.);
+        assert_eq!(left_length + right_length, 1000.);
+        assert_eq!(left.num_trees(), 1);
+        assert_eq!(right.num_trees(), 2);
+        assert_eq!(right.breakpoints(), &[0., 250., 750.]);
+        assert_eq!(left.sites().num_rows(), 1);
+        assert_eq!(left.sites().position(0).unwrap(), 100.);
+        assert_eq!(right.sites().num_rows(), 1);
+        assert_eq!(right.sites().position(0).unwrap(), 450.);
+        assert_eq!(right.mutations().node(0).unwrap(), 4);
+        assert_eq!(left.nodes().num_rows(), treeseq.nodes().num_rows());
+
+        for position in [0., 1000., -1., 1001., f64::NAN].iter() {
+            assert!(matches!(
+                treeseq.split_at(*position),
+                Err(TskitError::ValueError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_mutations_at_site() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(100., Some(b"0")).unwrap();
+        tables.add_site(200., Some(b"0")).unwrap();
+        tables.add_site(700., Some(b"0")).unwrap();
+        tables
+            .add_mutation(0, 2, TSK_NULL, 0.5, Some(b"1"))
+            .unwrap();
+        tables
+            .add_mutation(1, 5, TSK_NULL, 0.5, Some(b"1"))
+            .unwrap();
+        tables
+            .add_mutation(1, 4, TSK_NULL, 0.5, Some(b"1"))
+            .unwrap();
+        tables.add_mutation(1, 4, 2, 0.25, Some(b"2")).unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert_eq!(treeseq.mutations_at_site(0).unwrap(), vec![0]);
+        assert_eq!(treeseq.mutations_at_site(1).unwrap(), vec![1, 2, 3]);
+        assert!(treeseq.mutations_at_site(2).unwrap().is_empty());
+        for site in [TSK_NULL, 3].iter() {
+            assert!(matches!(
+                treeseq.mutations_at_site(*site),
+                Err(TskitError::IndexError)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_site_mutation_index() {
+        let mut tables = make_small_table_collection_two_trees();
+        for i in 0..50 {
+            let site = tables.add_site(i as f64 * 20., Some(b"0")).unwrap();
+            for j in 0..(i % 4) {
+                tables
+                    .add_mutation(site, 2 + j, TSK_NULL, 0.5, Some(b"1"))
+                    .unwrap();
+            }
+        }
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let index = treeseq.build_site_mutation_index();
+        assert_eq!(index.num_sites(), 50);
+        for site in 0..50 {
+            assert_eq!(
+                index.mutations(site).unwrap(),
+                treeseq.mutations_at_site(site).unwrap().as_slice()
+            );
+        }
+        for site in [TSK_NULL, 50].iter() {
+            assert!(matches!(
+                index.mutations(*site),
+                Err(TskitError::IndexError)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_allele_state() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(100., Some(b"A")).unwrap();
+        tables.add_site(700., Some(b"A")).unwrap();
+        tables
+            .add_mutation(0, 1, TSK_NULL, 1.5, Some(b"C"))
+            .unwrap();
+        tables.add_mutation(0, 4, 0, 0.5, Some(b"G")).unwrap();
+        tables
+            .add_mutation(1, 1, TSK_NULL, 1.5, Some(b"T"))
+            .unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+        // In the first tree, node 1 is the parent of 4 and 5.
+        assert_eq!(treeseq.allele_state(0, 2).unwrap(), b"A");
+        assert_eq!(treeseq.allele_state(0, 3).unwrap(), b"A");
+        assert_eq!(treeseq.allele_state(0, 4).unwrap(), b"G");
+        assert_eq!(treeseq.allele_state(0, 5).unwrap(), b"C");
+        assert_eq!(treeseq.allele_state(0, 1).unwrap(), b"C");
+        // In the second tree, node 1 is the parent of 2, 4, and 5.
+        assert_eq!(treeseq.allele_state(1, 2).unwrap(), b"T");
+        assert_eq!(treeseq.allele_state(1, 3).unwrap(), b"A");
+        assert_eq!(treeseq.allele_state(1, 4).unwrap(), b"T");
+
+        for (site, sample) in [(TSK_NULL, 2), (2, 2), (0, TSK_NULL), (0, 6)].iter() {
+            assert!(matches!(
+                treeseq.allele_state(*site, *sample),
+                Err(TskitError::IndexError)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        for treeseq in [
+            treeseq_from_small_table_collection(),
+            treeseq_from_small_table_collection_two_trees(),
+        ]
+        .iter()
+        {
+            let b = treeseq.breakpoints();
+            assert_eq!(b.len(), treeseq.num_trees() as usize + 1);
+            assert_eq!(b[0], 0.);
+            assert_eq!(b[b.len() - 1], 1000.);
+        }
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        assert_eq!(treeseq.breakpoints(), &[0., 500., 1000.]);
+    }
+
+    #[test]
+    fn test_num_trees_in_interval() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        for (left, right, n) in [
+            (0., 1000., 2),
+            (0., 500., 1),
+            (0., 500.5, 2),
+            (499., 501., 2),
+            (500., 1000., 1),
+            (250., 251., 1),
+            (999., 1000., 1),
+        ]
+        .iter()
+        {
+            assert_eq!(treeseq.num_trees_in_interval(*left, *right).unwrap(), *n);
+        }
+        for (left, right) in [(-1., 500.), (500., 500.), (0., 1001.)].iter() {
+            assert!(matches!(
+                treeseq.num_trees_in_interval(*left, *right),
+                Err(TskitError::ValueError {
+                    got: _,
+                    expected: _
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_sample_nodes_match_flags() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        assert!(treeseq.sample_nodes_match_flags());
+        let mut nodes = treeseq.nodes();
+        nodes.flags_array_mut()[2] &= !crate::TSK_NODE_IS_SAMPLE;
+        assert!(!treeseq.sample_nodes_match_flags());
+        nodes.flags_array_mut()[2] |= crate::TSK_NODE_IS_SAMPLE;
+        assert!(treeseq.sample_nodes_match_flags());
+        nodes.flags_array_mut()[0] |= crate::TSK_NODE_IS_SAMPLE;
+        assert!(!treeseq.sample_nodes_match_flags());
+    }
+
+    #[test]
+    fn test_samples_in_population() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_population().unwrap();
+        tables.add_population().unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        for pop in [0, 1, 1, 0, 1].iter() {
+            tables
+                .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, *pop, TSK_NULL)
+                .unwrap();
+        }
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert_eq!(treeseq.samples_in_population(0).unwrap(), vec![1, 4]);
+        assert_eq!(treeseq.samples_in_population(1).unwrap(), vec![2, 3, 5]);
+        for pop in [TSK_NULL, 2].iter() {
+            assert!(matches!(
+                treeseq.samples_in_population(*pop),
+                Err(TskitError::IndexError)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_sample_sets_by_population() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        for _ in 0..3 {
+            tables.add_population().unwrap();
+        }
+        tables.add_node(0, 1.0, 0, TSK_NULL).unwrap();
+        for pop in [1, 0, TSK_NULL, 1, 0, 1].iter() {
+            tables
+                .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, *pop, TSK_NULL)
+                .unwrap();
+        }
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let expected = vec![vec![2, 5], vec![1, 4, 6], vec![]];
+        assert_eq!(treeseq.sample_sets_by_population(), expected);
+        let mut buffer = vec![TSK_NULL; 20];
+        let sample_sets = treeseq.sample_sets_by_population_into(&mut buffer);
+        assert_eq!(sample_sets.len(), expected.len());
+        for (s, e) in sample_sets.iter().zip(expected.iter()) {
+            assert_eq!(s, e);
+        }
+        for (i, s) in sample_sets.iter().enumerate() {
+            assert_eq!(*s, treeseq.samples_in_population(i as tsk_id_t).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_nodes_for_individual() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_individual(0, &[], &[]).unwrap();
+        tables.add_individual(0, &[], &[]).unwrap();
+        tables.add_individual(0, &[], &[]).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        for ind in [1, 0, 0, 1].iter() {
+            tables
+                .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, TSK_NULL, *ind)
+                .unwrap();
+        }
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert_eq!(treeseq.nodes_for_individual(0).unwrap(), vec![2, 3]);
+        assert_eq!(treeseq.nodes_for_individual(1).unwrap(), vec![1, 4]);
+        assert!(treeseq.nodes_for_individual(2).unwrap().is_empty());
+        for ind in [TSK_NULL, 3].iter() {
+            assert!(matches!(
+                treeseq.nodes_for_individual(*ind),
+                Err(TskitError::IndexError)
+            ));
+        }
     }
 
-    fn nodes(&self) -> NodeTable {
-        NodeTable::new_from_table(unsafe { &(*self.inner.tables).nodes })
+    #[test]
+    fn test_num_mutations_on_branch() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(100., None).unwrap();
+        tables.add_site(200., None).unwrap();
+        tables.add_site(700., None).unwrap();
+        tables.add_mutation(0, 2, TSK_NULL, 0.5, None).unwrap();
+        tables.add_mutation(1, 2, TSK_NULL, 0.5, None).unwrap();
+        tables.add_mutation(1, 4, TSK_NULL, 0.5, None).unwrap();
+        tables.add_mutation(2, 2, TSK_NULL, 0.5, None).unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let expected = [[0, 0, 2, 0, 1, 0], [0, 0, 1, 0, 0, 0]];
+        let mut ntrees = 0;
+        while let Some(tree) = tree_iter.next() {
+            for u in 0..6 {
+                assert_eq!(
+                    tree.num_mutations_on_branch(u).unwrap(),
+                    expected[ntrees][u as usize]
+                );
+            }
+            for u in [TSK_NULL, 6].iter() {
+                assert!(matches!(
+                    tree.num_mutations_on_branch(*u),
+                    Err(TskitError::IndexError)
+                ));
+            }
+            ntrees += 1;
+        }
+        assert_eq!(ntrees, 2);
     }
 
-    fn sites(&self) -> SiteTable {
-        SiteTable::new_from_table(unsafe { &(*self.inner.tables).sites })
+    #[test]
+    fn test_ancestral_span() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_node(0, 3.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 4.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_edge(0., 500., 6, 0).unwrap();
+        let treeseq = tables
+            .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+            .unwrap();
+        for u in 0..6 {
+            assert!((treeseq.ancestral_span(u).unwrap() - 1000.).abs() <= 1e-12);
+        }
+        assert!((treeseq.ancestral_span(6).unwrap() - 500.).abs() <= 1e-12);
+        assert_eq!(treeseq.ancestral_span(7).unwrap(), 0.);
+        for u in [TSK_NULL, 8].iter() {
+            assert!(matches!(
+                treeseq.ancestral_span(*u),
+                Err(TskitError::IndexError)
+            ));
+        }
     }
 
-    fn mutations(&self) -> MutationTable {
-        MutationTable::new_from_table(unsafe { &(*self.inner.tables).mutations })
+    #[test]
+    fn test_mean_num_roots() {
+        let treeseq = treeseq_from_small_table_collection();
+        assert!((treeseq.mean_num_roots().unwrap() - 1.0).abs() <= 1e-12);
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        assert!((treeseq.mean_num_roots().unwrap() - 1.5).abs() <= 1e-12);
     }
 
-    fn populations(&self) -> PopulationTable {
-        PopulationTable::new_from_table(unsafe { &(*self.inner.tables).populations })
-    }
-}
+    #[test]
+    fn test_first_and_last_tree() {
+        let treeseq = treeseq_from_small_table_collection();
+        let first = treeseq.first_tree(TreeFlags::default()).unwrap();
+        let last = treeseq.last_tree(TreeFlags::default()).unwrap();
+        assert_eq!(first.interval(), (0., 1000.));
+        assert_eq!(last.interval(), (0., 1000.));
+        assert_eq!(first.num_tracked_samples(0).unwrap(), 2);
 
-impl crate::traits::NodeListGenerator for TreeSequence {}
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut first = treeseq.first_tree(TreeFlags::default()).unwrap();
+        let mut last = treeseq.last_tree(TreeFlags::default()).unwrap();
+        assert_eq!(first.interval(), (0., 500.));
+        assert_eq!(last.interval(), (500., 1000.));
+        assert_eq!(first.num_roots(), 2);
+        assert_eq!(last.num_roots(), 1);
 
-#[cfg(any(doc, feature = "provenance"))]
-impl crate::provenance::Provenance for TreeSequence {
-    fn add_provenance(&mut self, record: &str) -> TskReturnValue {
-        if record.is_empty() {
-            return Err(TskitError::ValueError {
-                got: String::from("empty string slice"),
-                expected: String::from("non-empty string slice"),
-            });
-        }
-        let timestamp = chrono::prelude::Local::now().to_rfc3339();
-        let rv = unsafe {
-            ll_bindings::tsk_provenance_table_add_row(
-                &mut (*self.inner.tables).provenances,
-                timestamp.as_ptr() as *mut i8,
-                timestamp.len() as tsk_size_t,
-                record.as_ptr() as *mut i8,
-                record.len() as tsk_size_t,
-            )
-        };
-        handle_tsk_return_value!(rv)
+        // The trees can still be moved.
+        assert_eq!(first.next().unwrap().interval(), (500., 1000.));
+        assert_eq!(last.next_back().unwrap().interval(), (0., 500.));
     }
 
-    fn provenances(&self) -> crate::provenance::ProvenanceTable {
-        crate::provenance::ProvenanceTable::new_from_table(unsafe {
-            &(*self.inner.tables).provenances
-        })
+    #[test]
+    fn test_span_weighted_mean() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mean = treeseq
+            .span_weighted_mean(TreeFlags::default(), |t| t.total_branch_length(false))
+            .unwrap();
+        // Each tree has a total branch length of 6.
+        assert!((mean - 6.0).abs() <= 1e-12);
+
+        let mut calls = 0;
+        let rv = treeseq.span_weighted_mean(TreeFlags::default(), |_| {
+            calls += 1;
+            Err(TskitError::IndexError)
+        });
+        assert!(matches!(rv, Err(TskitError::IndexError)));
+        assert_eq!(calls, 1);
     }
-}
 
-#[cfg(test)]
-pub(crate) mod test_trees {
-    use super::*;
-    use crate::test_fixtures::{
-        make_small_table_collection, make_small_table_collection_two_trees,
-        treeseq_from_small_table_collection, treeseq_from_small_table_collection_two_trees,
-    };
-    use streaming_iterator::DoubleEndedStreamingIterator;
-    use streaming_iterator::StreamingIterator;
+    #[test]
+    fn test_into_tables() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let copy = treeseq.dump_tables().unwrap();
+        let tables = treeseq.into_tables().unwrap();
+        assert!(tables.equals(&copy, crate::TableEqualityOptions::default()));
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert_eq!(treeseq.num_trees(), 2);
+    }
 
     #[test]
-    fn test_create_treeseq_new_from_tables() {
-        let tables = make_small_table_collection();
-        let treeseq = TreeSequence::new(tables, TreeSequenceFlags::default()).unwrap();
-        let samples = treeseq.sample_nodes();
-        assert_eq!(samples.len(), 2);
-        for i in 1..3 {
-            assert_eq!(samples[i - 1], i as tsk_id_t);
+    fn test_iterate_trees_from_threads() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let handles = (0..4)
+            .map(|_| {
+                let ts = treeseq
+                    .dump_tables()
+                    .unwrap()
+                    .tree_sequence(TreeSequenceFlags::default())
+                    .unwrap();
+                std::thread::spawn(move || {
+                    let mut num_trees = 0;
+                    let mut tree_iter = ts.tree_iterator(TreeFlags::default()).unwrap();
+                    while tree_iter.next().is_some() {
+                        num_trees += 1;
+                    }
+                    let samples = ts.sample_nodes().to_vec();
+                    let d = ts
+                        .diversity(&[&samples], None, crate::StatisticsMode::Branch)
+                        .unwrap();
+                    (num_trees, d)
+                })
+            })
+            .collect::<Vec<_>>();
+        let expected = treeseq
+            .diversity(
+                &[treeseq.sample_nodes()],
+                None,
+                crate::StatisticsMode::Branch,
+            )
+            .unwrap();
+        for h in handles {
+            let (num_trees, d) = h.join().unwrap();
+            assert_eq!(num_trees, treeseq.num_trees());
+            assert_eq!(d, expected);
         }
     }
 
     #[test]
-    fn test_create_treeseq_from_tables() {
-        let tables = make_small_table_collection();
-        let _treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    fn test_trees() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let trees = treeseq
+            .trees(TreeFlags::default())
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(trees.len(), treeseq.num_trees() as usize);
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        for info in trees.iter() {
+            let tree = tree_iter.next().unwrap();
+            assert_eq!(info.interval, tree.interval());
+            assert_eq!(info.roots, tree.roots_to_vec());
+        }
+        assert_eq!(trees[0].index, 0);
+        assert_eq!(trees[1].index, 1);
+        assert_eq!(trees.iter().map(|t| t.span()).sum::<f64>(), 1000.);
     }
 
     #[test]
-    fn test_iterate_tree_seq_with_one_tree() {
-        let tables = make_small_table_collection();
-        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
-        let mut ntrees = 0;
+    fn test_roots_exclude_virtual_root() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
         let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let mut expected_roots = vec![vec![0, 1], vec![0]].into_iter();
         while let Some(tree) = tree_iter.next() {
-            ntrees += 1;
-            assert_eq!(tree.current_tree, ntrees);
-            let samples = tree.sample_nodes();
-            assert_eq!(samples.len(), 2);
-            for i in 1..3 {
-                assert_eq!(samples[i - 1], i as tsk_id_t);
-
-                let mut nsteps = 0;
-                for _ in tree.parents(samples[i - 1]).unwrap() {
-                    nsteps += 1;
-                }
-                assert_eq!(nsteps, 2);
-            }
             let roots = tree.roots_to_vec();
-            for r in roots.iter() {
-                let mut num_children = 0;
-                for _ in tree.children(*r).unwrap() {
-                    num_children += 1;
-                }
-                assert_eq!(num_children, 2);
-            }
+            assert_eq!(roots, expected_roots.next().unwrap());
+            assert_eq!(roots.len(), tree.num_roots() as usize);
+            assert!(!roots.contains(&tree.virtual_root()));
+            assert_eq!(
+                tree.children(tree.virtual_root())
+                    .unwrap()
+                    .collect::<Vec<_>>(),
+                roots
+            );
+            assert!(tree
+                .traverse_nodes(NodeTraversalOrder::Preorder)
+                .all(|u| u != tree.virtual_root()));
+            assert_eq!(tree.parent_array().len(), tree.virtual_root() as usize);
         }
-        assert_eq!(ntrees, 1);
     }
 
     #[test]
-    fn test_iterate_no_roots() {
-        let mut tables = TableCollection::new(100.).unwrap();
-        tables.build_index().unwrap();
-        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+    fn test_sackin_and_colless_indexes() {
+        let treeseq = treeseq_from_small_table_collection();
         let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
         while let Some(tree) = tree_iter.next() {
-            let mut num_roots = 0;
-            for _ in tree.roots() {
-                num_roots += 1;
+            assert_eq!(tree.sackin_index().unwrap(), 2);
+            assert_eq!(tree.colless_index().unwrap(), 0);
+        }
+
+        // The second tree has a node with three children.
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let mut expected_sackin = vec![4, 7].into_iter();
+        while let Some(tree) = tree_iter.next() {
+            assert_eq!(
+                tree.sackin_index().unwrap(),
+                expected_sackin.next().unwrap()
+            );
+            if tree.interval().0 == 0. {
+                assert_eq!(tree.colless_index().unwrap(), 0);
+            } else {
+                assert!(tree.colless_index().is_err());
             }
-            assert_eq!(num_roots, 0);
         }
-    }
 
-    #[should_panic]
-    #[test]
-    fn test_samples_iterator_error_when_not_tracking_samples() {
-        let tables = make_small_table_collection();
+        //     0
+        //   +-+-+
+        //   1   |
+        // +-+-+ |
+        // 2   3 4
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_node(0, 2.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        for _ in 0..3 {
+            tables
+                .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, TSK_NULL, TSK_NULL)
+                .unwrap();
+        }
+        tables.add_edge(0., 1., 1, 2).unwrap();
+        tables.add_edge(0., 1., 1, 3).unwrap();
+        tables.add_edge(0., 1., 0, 1).unwrap();
+        tables.add_edge(0., 1., 0, 4).unwrap();
+        tables.build_index().unwrap();
         let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
-
         let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
-        if let Some(tree) = tree_iter.next() {
-            for n in tree.traverse_nodes(NodeTraversalOrder::Preorder) {
-                for _ in tree.samples(n).unwrap() {}
-            }
+        while let Some(tree) = tree_iter.next() {
+            assert_eq!(tree.sackin_index().unwrap(), 5);
+            assert_eq!(tree.colless_index().unwrap(), 1);
         }
     }
 
     #[test]
-    fn test_num_tracked_samples() {
-        let treeseq = treeseq_from_small_table_collection();
-        assert_eq!(treeseq.inner.num_samples, 2);
+    fn test_subtree_sizes() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
         let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
-        if let Some(tree) = tree_iter.next() {
-            assert_eq!(tree.num_tracked_samples(2).unwrap(), 1);
-            assert_eq!(tree.num_tracked_samples(1).unwrap(), 1);
-            assert_eq!(tree.num_tracked_samples(0).unwrap(), 2);
+        let mut expected = vec![vec![2, 2, 1, 1, 1, 1], vec![4, 3, 1, 1, 1, 1]].into_iter();
+        while let Some(tree) = tree_iter.next() {
+            assert_eq!(tree.subtree_sizes().unwrap(), expected.next().unwrap());
+        }
+
+        // Node 2 is not in the tree.
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_node(0, 1.0, TSK_NULL, TSK_NULL).unwrap();
+        tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, TSK_NULL, TSK_NULL)
+            .unwrap();
+        tables.add_node(0, 0.0, TSK_NULL, TSK_NULL).unwrap();
+        tables.add_edge(0., 1., 0, 1).unwrap();
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            assert_eq!(tree.subtree_sizes().unwrap(), vec![1, 1, 0]);
         }
     }
 
-    #[should_panic]
     #[test]
-    fn test_num_tracked_samples_not_tracking_samples() {
-        let treeseq = treeseq_from_small_table_collection();
-        assert_eq!(treeseq.inner.num_samples, 2);
-        let mut tree_iter = treeseq.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS).unwrap();
-        if let Some(tree) = tree_iter.next() {
-            assert_eq!(tree.num_tracked_samples(2).unwrap(), 0);
-            assert_eq!(tree.num_tracked_samples(1).unwrap(), 0);
-            assert_eq!(tree.num_tracked_samples(0).unwrap(), 0);
+    fn test_postorder() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let mut expected = vec![vec![2, 3, 0, 4, 5, 1], vec![3, 4, 5, 2, 1, 0]].into_iter();
+        while let Some(tree) = tree_iter.next() {
+            let postorder = tree.postorder().unwrap();
+            assert_eq!(postorder, expected.next().unwrap());
+            let mut seen = vec![false; treeseq.nodes().num_rows() as usize];
+            for &u in &postorder {
+                assert!(!seen[u as usize]);
+                for c in tree.children(u).unwrap() {
+                    assert!(seen[c as usize]);
+                }
+                seen[u as usize] = true;
+            }
+            let mut preorder = tree
+                .traverse_nodes(NodeTraversalOrder::Preorder)
+                .collect::<Vec<_>>();
+            let mut sorted = postorder.clone();
+            preorder.sort_unstable();
+            sorted.sort_unstable();
+            assert_eq!(sorted, preorder);
         }
     }
 
     #[test]
-    fn test_iterate_samples() {
-        let tables = make_small_table_collection();
-        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
-
-        let mut tree_iter = treeseq.tree_iterator(TreeFlags::SAMPLE_LISTS).unwrap();
-        if let Some(tree) = tree_iter.next() {
-            assert!(!tree.flags.contains(TreeFlags::NO_SAMPLE_COUNTS));
-            assert!(tree.flags.contains(TreeFlags::SAMPLE_LISTS));
-            let mut s = vec![];
-            for i in tree.samples(0).unwrap() {
-                s.push(i);
+    fn test_edge_to_parent() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let edges = treeseq.edges();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            let (tree_left, tree_right) = tree.interval();
+            for &s in tree.sample_nodes() {
+                let e = tree.edge_to_parent(s).unwrap().unwrap();
+                assert_eq!(edges.child(e).unwrap(), s);
+                assert_eq!(edges.parent(e).unwrap(), tree.parent(s).unwrap());
+                assert!(edges.left(e).unwrap() <= tree_left);
+                assert!(edges.right(e).unwrap() >= tree_right);
             }
-            assert_eq!(s.len(), 2);
-            assert_eq!(s.len(), tree.num_tracked_samples(0).unwrap() as usize);
-            assert_eq!(s[0], 1);
-            assert_eq!(s[1], 2);
-
-            for u in 1..3 {
-                let mut s = vec![];
-                for i in tree.samples(u).unwrap() {
-                    s.push(i);
-                }
-                assert_eq!(s.len(), 1);
-                assert_eq!(s[0], u);
-                assert_eq!(s.len(), tree.num_tracked_samples(u).unwrap() as usize);
+            for root in tree.roots() {
+                assert!(tree.edge_to_parent(root).unwrap().is_none());
             }
-        } else {
-            panic!("Expected a tree");
+            assert!(tree.edge_to_parent(tree.virtual_root()).unwrap().is_none());
+            assert!(matches!(
+                tree.edge_to_parent(-2),
+                Err(TskitError::IndexError)
+            ));
         }
     }
 
     #[test]
-    fn test_iterate_samples_two_trees() {
+    fn test_tree_edges() {
         let treeseq = treeseq_from_small_table_collection_two_trees();
-        assert_eq!(treeseq.inner.num_trees, 2);
-        let mut tree_iter = treeseq.tree_iterator(TreeFlags::SAMPLE_LISTS).unwrap();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let mut num_trees = 0;
         while let Some(tree) = tree_iter.next() {
-            for n in tree.traverse_nodes(NodeTraversalOrder::Preorder) {
-                let mut nsamples = 0;
-                for _ in tree.samples(n).unwrap() {
-                    nsamples += 1;
-                }
-                assert!(nsamples > 0);
-                assert_eq!(nsamples, tree.num_tracked_samples(n).unwrap());
+            let edges = tree.edges().collect::<Vec<_>>();
+            let num_non_roots = tree
+                .traverse_nodes(NodeTraversalOrder::Preorder)
+                .filter(|&u| tree.parent(u).unwrap() != TSK_NULL)
+                .count();
+            assert_eq!(edges.len(), num_non_roots);
+            assert!(edges.windows(2).all(|w| w[0] < w[1]));
+            for e in edges {
+                let child = treeseq.edges().child(e).unwrap();
+                assert_eq!(tree.edge_to_parent(child).unwrap(), Some(e));
             }
+            num_trees += 1;
         }
+        assert_eq!(num_trees, 2);
     }
 
+    #[cfg(feature = "json")]
     #[test]
-    fn test_kc_distance_naive_test() {
-        let ts1 = treeseq_from_small_table_collection();
-        let ts2 = treeseq_from_small_table_collection();
-
-        let kc = ts1.kc_distance(&ts2, 0.0).unwrap();
-        assert!(kc.is_finite());
-        assert!((kc - 0.).abs() < f64::EPSILON);
+    fn test_to_json_summary() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let json = treeseq.to_json_summary().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["num_trees"], 2);
+        assert_eq!(value["num_samples"], 4);
+        assert_eq!(value["sequence_length"], 1000.0);
+        assert_eq!(
+            value["breakpoints"],
+            serde_json::json!([0.0, 500.0, 1000.0])
+        );
+        let trees = value["trees"].as_array().unwrap();
+        assert_eq!(trees.len(), 2);
+        assert_eq!(trees[0]["roots"].as_array().unwrap().len(), 2);
+        assert_eq!(trees[1]["roots"], serde_json::json!([0]));
+        assert_eq!(trees[1]["left"], 500.0);
     }
 
     #[test]
-    fn test_dump_tables() {
-        let tables = make_small_table_collection_two_trees();
-        // Have to make b/c tables will no longer exist after making the treeseq
-        let tables_copy = tables.deepcopy().unwrap();
-        let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
-        let dumped = ts.dump_tables().unwrap();
-        assert!(tables_copy.equals(&dumped, crate::TableEqualityOptions::default()));
+    fn test_topology_iterator() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut trees = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let mut topologies = treeseq.topology_iterator().unwrap();
+        let mut num_trees = 0;
+        while let Some(tree) = trees.next() {
+            let topology = topologies.next().unwrap();
+            assert_eq!(tree.interval(), topology.interval());
+            assert_eq!(tree.parent_array(), topology.parent_array());
+            assert_eq!(tree.left_child_array(), topology.left_child_array());
+            assert_eq!(tree.right_child_array(), topology.right_child_array());
+            assert!(matches!(
+                topology.num_tracked_samples(2),
+                Err(TskitError::NotTrackingSamples)
+            ));
+            num_trees += 1;
+        }
+        assert!(topologies.next().is_none());
+        assert_eq!(num_trees, treeseq.num_trees());
     }
 
     #[test]
-    fn test_reverse_tree_iteration() {
+    fn test_root_of() {
         let treeseq = treeseq_from_small_table_collection_two_trees();
         let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
-        let mut starts_fwd = vec![];
-        let mut stops_fwd = vec![];
-        let mut starts_rev = vec![];
-        let mut stops_rev = vec![];
+        if let Some(tree) = tree_iter.next() {
+            assert_eq!(tree.root_of(2).unwrap(), 0);
+            assert_eq!(tree.root_of(3).unwrap(), tree.root_of(2).unwrap());
+            assert_eq!(tree.root_of(4).unwrap(), 1);
+            assert_eq!(tree.root_of(5).unwrap(), 1);
+            assert_eq!(tree.root_of(0).unwrap(), 0);
+            assert!(matches!(tree.root_of(-2), Err(TskitError::IndexError)));
+            assert!(matches!(tree.root_of(7), Err(TskitError::IndexError)));
+        }
+        if let Some(tree) = tree_iter.next() {
+            for u in 0..6 {
+                assert_eq!(tree.root_of(u).unwrap(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tree_num_samples() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS).unwrap();
         while let Some(tree) = tree_iter.next() {
-            let interval = tree.interval();
-            starts_fwd.push(interval.0);
-            stops_fwd.push(interval.1);
+            assert_eq!(tree.num_samples(), 4);
+            assert_eq!(tree.num_samples(), treeseq.num_samples());
+            assert_eq!(tree.sample_nodes().len(), 4);
         }
-        assert_eq!(stops_fwd.len(), 2);
-        assert_eq!(stops_fwd.len(), 2);
+    }
 
-        // NOTE: we do NOT need to create a new iterator.
-        while let Some(tree) = tree_iter.next_back() {
-            let interval = tree.interval();
-            starts_rev.push(interval.0);
-            stops_rev.push(interval.1);
+    #[test]
+    fn test_node_arrays() {
+        let treeseq = treeseq_from_small_table_collection();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        while let Some(tree) = tree_iter.next() {
+            let parents = tree.parent_node_array();
+            assert_eq!(parents.len(), 3);
+            assert_eq!(parents.get(0), None);
+            assert_eq!(parents.get(1), Some(0));
+            assert_eq!(parents.get(2), Some(0));
+            assert_eq!(parents.get(3), None);
+            assert_eq!(parents.get(TSK_NULL), None);
+            assert_eq!(parents.iter().collect::<Vec<_>>(), vec![(1, 0), (2, 0)]);
+
+            let left_children = tree.left_child_node_array();
+            assert_eq!(left_children.iter().count(), 1);
+            assert_eq!(left_children.get(0), Some(1));
+            let right_children = tree.right_child_node_array();
+            assert_eq!(right_children.get(0), Some(2));
+            assert_eq!(
+                tree.left_sib_node_array().iter().collect::<Vec<_>>(),
+                vec![(2, 1)]
+            );
+            assert_eq!(
+                tree.right_sib_node_array().iter().collect::<Vec<_>>(),
+                vec![(1, 2)]
+            );
         }
-        assert_eq!(starts_fwd.len(), starts_rev.len());
-        assert_eq!(stops_fwd.len(), stops_rev.len());
+    }
 
-        starts_rev.reverse();
-        assert!(starts_fwd == starts_rev);
-        stops_rev.reverse();
-        assert!(stops_fwd == stops_rev);
+    #[test]
+    fn test_tree_snapshots() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        let snapshots = treeseq
+            .tree_snapshots(TreeFlags::default())
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(snapshots.len(), treeseq.num_trees() as usize);
+        assert_eq!(snapshots[0].interval, (0., 500.));
+        assert_eq!(snapshots[0].roots, vec![0, 1]);
+        assert_eq!(snapshots[1].roots, vec![0]);
+        assert_eq!(snapshots[1].parents, vec![TSK_NULL, 0, 1, 0, 1, 1]);
+        assert_eq!(snapshots[1].parent_node_array().get(3), Some(0));
+        let multi_root = treeseq
+            .tree_snapshots(TreeFlags::default())
+            .unwrap()
+            .filter(|t| t.roots.len() > 1)
+            .count();
+        assert_eq!(multi_root, 1);
     }
 
     // FIXME: remove later
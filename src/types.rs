@@ -12,6 +12,16 @@ use crate::bindings as ll_bindings;
 /// the first row from which to begin.
 /// The names of the fields are the same
 /// names as tables in a TableCollection.
+///
+/// The offsets may be set directly or
+/// via the `at_*` methods:
+///
+/// ```
+/// let b = tskit::types::Bookmark::new().at_edge(100).at_migration(3);
+/// assert_eq!(b.offsets.edges, 100);
+/// assert_eq!(b.offsets.migrations, 3);
+/// assert_eq!(b.offsets.nodes, 0);
+/// ```
 pub struct Bookmark {
     pub offsets: ll_bindings::tsk_bookmark_t,
 }
@@ -31,6 +41,54 @@ impl Bookmark {
             },
         }
     }
+
+    /// Set the offset into the individual table to `row`.
+    pub const fn at_individual(mut self, row: ll_bindings::tsk_size_t) -> Self {
+        self.offsets.individuals = row;
+        self
+    }
+
+    /// Set the offset into the node table to `row`.
+    pub const fn at_node(mut self, row: ll_bindings::tsk_size_t) -> Self {
+        self.offsets.nodes = row;
+        self
+    }
+
+    /// Set the offset into the edge table to `row`.
+    pub const fn at_edge(mut self, row: ll_bindings::tsk_size_t) -> Self {
+        self.offsets.edges = row;
+        self
+    }
+
+    /// Set the offset into the migration table to `row`.
+    pub const fn at_migration(mut self, row: ll_bindings::tsk_size_t) -> Self {
+        self.offsets.migrations = row;
+        self
+    }
+
+    /// Set the offset into the site table to `row`.
+    pub const fn at_site(mut self, row: ll_bindings::tsk_size_t) -> Self {
+        self.offsets.sites = row;
+        self
+    }
+
+    /// Set the offset into the mutation table to `row`.
+    pub const fn at_mutation(mut self, row: ll_bindings::tsk_size_t) -> Self {
+        self.offsets.mutations = row;
+        self
+    }
+
+    /// Set the offset into the population table to `row`.
+    pub const fn at_population(mut self, row: ll_bindings::tsk_size_t) -> Self {
+        self.offsets.populations = row;
+        self
+    }
+
+    /// Set the offset into the provenance table to `row`.
+    pub const fn at_provenance(mut self, row: ll_bindings::tsk_size_t) -> Self {
+        self.offsets.provenances = row;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -52,4 +110,25 @@ mod test {
         b.offsets.nodes = 3;
         assert_eq!(b.offsets.nodes, 3);
     }
+
+    #[test]
+    fn test_bookmark_builders() {
+        let b = Bookmark::new()
+            .at_individual(1)
+            .at_node(2)
+            .at_edge(3)
+            .at_migration(4)
+            .at_site(5)
+            .at_mutation(6)
+            .at_population(7)
+            .at_provenance(8);
+        assert_eq!(b.offsets.individuals, 1);
+        assert_eq!(b.offsets.nodes, 2);
+        assert_eq!(b.offsets.edges, 3);
+        assert_eq!(b.offsets.migrations, 4);
+        assert_eq!(b.offsets.sites, 5);
+        assert_eq!(b.offsets.mutations, 6);
+        assert_eq!(b.offsets.populations, 7);
+        assert_eq!(b.offsets.provenances, 8);
+    }
 }
@@ -6,3 +6,68 @@ pub(crate) fn f64_partial_cmp_equal(a: &f64, b: &f64) -> bool {
         None => false,
     }
 }
+
+// Check that all ids in `samples` are non-null
+// node ids less than `num_nodes`.
+pub(crate) fn validate_sample_ids(
+    samples: &[crate::tsk_id_t],
+    num_nodes: crate::tsk_size_t,
+) -> Result<(), crate::TskitError> {
+    for &s in samples {
+        if s == crate::TSK_NULL {
+            return Err(crate::TskitError::ValueError {
+                got: String::from("TSK_NULL"),
+                expected: String::from("a non-null sample id"),
+            });
+        }
+        if s < 0 || s >= num_nodes as crate::tsk_id_t {
+            return Err(crate::TskitError::ValueError {
+                got: format!("sample id {}", s),
+                expected: format!("sample id in [0, {})", num_nodes),
+            });
+        }
+    }
+    Ok(())
+}
+
+// Return a ragged column and its offsets without copying.
+// The offset array has num_rows + 1 entries.
+pub(crate) fn ragged_column_raw<'a>(
+    column: *const libc::c_char,
+    column_offset: *const crate::tsk_size_t,
+    num_rows: crate::tsk_size_t,
+    column_length: crate::tsk_size_t,
+) -> (&'a [u8], &'a [crate::tsk_size_t]) {
+    let column = if column_length == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(column as *const u8, column_length as usize) }
+    };
+    let offsets = unsafe { std::slice::from_raw_parts(column_offset, num_rows as usize + 1) };
+    (column, offsets)
+}
+
+// Borrow row `row` of a ragged column as a string slice.
+pub(crate) fn char_column_to_str<'a>(
+    column: *const libc::c_char,
+    column_offset: *const crate::tsk_size_t,
+    row: crate::tsk_id_t,
+    num_rows: crate::tsk_size_t,
+    column_length: crate::tsk_size_t,
+) -> Result<Option<&'a str>, crate::TskitError> {
+    if row < 0 || (row as crate::tsk_size_t) >= num_rows {
+        return Err(crate::TskitError::IndexError {});
+    }
+    let (column, offsets) = ragged_column_raw(column, column_offset, num_rows, column_length);
+    let bytes = &column[offsets[row as usize] as usize..offsets[row as usize + 1] as usize];
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(s) => Ok(Some(s)),
+        Err(e) => Err(crate::TskitError::ValueError {
+            got: e.to_string(),
+            expected: String::from("valid UTF-8"),
+        }),
+    }
+}